@@ -1,12 +1,22 @@
 pub use sea_orm_migration::prelude::*;
 
 mod m20220101_000001_create_table;
+mod m20251018_000001_create_indexer_state;
+mod m20251019_000001_create_collection_stats;
+mod m20251020_000001_create_launch_event;
+mod m20251020_000002_whitelist_address_launch_key;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20220101_000001_create_table::Migration)]
+        vec![
+            Box::new(m20220101_000001_create_table::Migration),
+            Box::new(m20251018_000001_create_indexer_state::Migration),
+            Box::new(m20251019_000001_create_collection_stats::Migration),
+            Box::new(m20251020_000001_create_launch_event::Migration),
+            Box::new(m20251020_000002_whitelist_address_launch_key::Migration),
+        ]
     }
 }
@@ -0,0 +1,37 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // One row per pipeline holding the last checkpoint whose writes are fully committed
+        manager
+            .create_table(
+                Table::create()
+                    .table(IndexerState::Table)
+                    .if_not_exists()
+                    .col(string(IndexerState::pipeline).not_null().primary_key())
+                    .col(big_integer(IndexerState::checkpoint).not_null())
+                    .col(date_time(IndexerState::updated_at).not_null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(IndexerState::Table).to_owned())
+            .await
+    }
+}
+
+#[allow(non_camel_case_types)]
+#[derive(DeriveIden)]
+enum IndexerState {
+    Table,
+    pipeline,
+    checkpoint,
+    updated_at,
+}
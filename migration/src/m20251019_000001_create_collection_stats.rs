@@ -0,0 +1,198 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Marketplace figures per collection, kept up to date by the indexer
+        manager
+            .create_table(
+                Table::create()
+                    .table(CollectionStats::Table)
+                    .if_not_exists()
+                    .col(
+                        string(CollectionStats::collection_type)
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(big_integer(CollectionStats::floor_price).null())
+                    .col(big_integer(CollectionStats::listed_count).not_null())
+                    .col(big_integer(CollectionStats::best_offer).null())
+                    .col(big_integer(CollectionStats::volume_24h).not_null())
+                    .col(big_integer(CollectionStats::volume_7d).not_null())
+                    .col(date_time(CollectionStats::updated_at).not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Hourly sale volume, the rolling windows are summed from the last 168 buckets
+        manager
+            .create_table(
+                Table::create()
+                    .table(CollectionVolume::Table)
+                    .if_not_exists()
+                    .col(string(CollectionVolume::collection_type).not_null())
+                    .col(date_time(CollectionVolume::hour).not_null())
+                    .col(big_integer(CollectionVolume::volume).not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(CollectionVolume::collection_type)
+                            .col(CollectionVolume::hour),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // The marketplace tables belong to its own schema and are absent from a database
+        // built from these migrations alone, leaving nothing to index or seed from.
+        let marketplace =
+            manager.has_table("Listing").await? && manager.has_table("CollectionOffer").await?;
+        if marketplace {
+            // The floor and the best offer are looked up again when the row holding them goes
+            // away. These tables use the column names of the marketplace schema.
+            manager
+                .create_index(
+                    Index::create()
+                        .name("idx_listing_collection_price")
+                        .table(Alias::new("Listing"))
+                        .col(Alias::new("collectionType"))
+                        .col(Alias::new("price"))
+                        .if_not_exists()
+                        .to_owned(),
+                )
+                .await?;
+            manager
+                .create_index(
+                    Index::create()
+                        .name("idx_collection_offer_collection_price")
+                        .table(Alias::new("CollectionOffer"))
+                        .col(Alias::new("collectionType"))
+                        .col(Alias::new("price"))
+                        .if_not_exists()
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        // The indexer only adjusts the figures as rows change, so they start from what the
+        // marketplace tables already hold. Volume is rebuilt from the sales of the past week.
+        let db = manager.get_connection();
+        if marketplace {
+            db.execute_unprepared(
+                r#"INSERT INTO collection_stats
+                    (collection_type, floor_price, listed_count, best_offer, volume_24h, volume_7d, updated_at)
+                SELECT collections.collection_type, listings.floor_price,
+                    COALESCE(listings.listed_count, 0), offers.best_offer, 0, 0, NOW()
+                FROM (
+                    SELECT "collectionType" AS collection_type FROM "Listing"
+                    UNION
+                    SELECT "collectionType" FROM "CollectionOffer"
+                ) collections
+                LEFT JOIN (
+                    SELECT "collectionType" AS collection_type, MIN(price) AS floor_price,
+                        COUNT(*) AS listed_count
+                    FROM "Listing"
+                    GROUP BY "collectionType"
+                ) listings USING (collection_type)
+                LEFT JOIN (
+                    SELECT "collectionType" AS collection_type, MAX(price) AS best_offer
+                    FROM "CollectionOffer"
+                    GROUP BY "collectionType"
+                ) offers USING (collection_type)
+                ON CONFLICT (collection_type) DO NOTHING"#,
+            )
+            .await?;
+        }
+        if manager.has_table("NftEvent").await? {
+            db.execute_unprepared(
+                r#"INSERT INTO collection_volume (collection_type, hour, volume)
+                SELECT "collectionType", date_trunc('hour', "createdAt"), SUM(price)
+                FROM "NftEvent"
+                WHERE type IN ('buy', 'accept_offer', 'accept_collection_offer')
+                    AND price IS NOT NULL
+                    AND "createdAt" >= date_trunc('hour', NOW()) - INTERVAL '167 hours'
+                GROUP BY 1, 2
+                ON CONFLICT (collection_type, hour) DO NOTHING"#,
+            )
+            .await?;
+            db.execute_unprepared(
+                r#"INSERT INTO collection_stats
+                    (collection_type, floor_price, listed_count, best_offer, volume_24h, volume_7d, updated_at)
+                SELECT DISTINCT collection_type, NULL::BIGINT, 0, NULL::BIGINT, 0, 0, NOW()
+                FROM collection_volume
+                ON CONFLICT (collection_type) DO NOTHING"#,
+            )
+            .await?;
+            db.execute_unprepared(
+                r#"UPDATE collection_stats SET
+                    volume_24h = (
+                        SELECT COALESCE(SUM(volume), 0) FROM collection_volume
+                        WHERE collection_volume.collection_type = collection_stats.collection_type
+                            AND hour >= date_trunc('hour', NOW()) - INTERVAL '23 hours'
+                    ),
+                    volume_7d = (
+                        SELECT COALESCE(SUM(volume), 0) FROM collection_volume
+                        WHERE collection_volume.collection_type = collection_stats.collection_type
+                    )"#,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if manager.has_table("CollectionOffer").await? {
+            manager
+                .drop_index(
+                    Index::drop()
+                        .name("idx_collection_offer_collection_price")
+                        .table(Alias::new("CollectionOffer"))
+                        .if_exists()
+                        .to_owned(),
+                )
+                .await?;
+        }
+        if manager.has_table("Listing").await? {
+            manager
+                .drop_index(
+                    Index::drop()
+                        .name("idx_listing_collection_price")
+                        .table(Alias::new("Listing"))
+                        .if_exists()
+                        .to_owned(),
+                )
+                .await?;
+        }
+        manager
+            .drop_table(Table::drop().table(CollectionVolume::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(CollectionStats::Table).to_owned())
+            .await
+    }
+}
+
+#[allow(non_camel_case_types)]
+#[derive(DeriveIden)]
+enum CollectionStats {
+    Table,
+    collection_type,
+    floor_price,
+    listed_count,
+    best_offer,
+    volume_24h,
+    volume_7d,
+    updated_at,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(DeriveIden)]
+enum CollectionVolume {
+    Table,
+    collection_type,
+    hour,
+    volume,
+}
@@ -0,0 +1,47 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Launchpad events already applied to their launch, so a re-indexed checkpoint does
+        // not apply them twice. Events are identified by transaction digest and position.
+        manager
+            .create_table(
+                Table::create()
+                    .table(LaunchEvent::Table)
+                    .if_not_exists()
+                    .col(string(LaunchEvent::digest).not_null())
+                    .col(big_integer(LaunchEvent::event_seq).not_null())
+                    .col(string(LaunchEvent::launch_id).not_null())
+                    .col(string(LaunchEvent::kind).not_null())
+                    .col(date_time(LaunchEvent::created_at).not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(LaunchEvent::digest)
+                            .col(LaunchEvent::event_seq),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(LaunchEvent::Table).to_owned())
+            .await
+    }
+}
+
+#[allow(non_camel_case_types)]
+#[derive(DeriveIden)]
+enum LaunchEvent {
+    Table,
+    digest,
+    event_seq,
+    launch_id,
+    kind,
+    created_at,
+}
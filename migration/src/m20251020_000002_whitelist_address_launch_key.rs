@@ -0,0 +1,43 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // An address can be whitelisted for several launches, each with its own allocation.
+        // This table belongs to the marketplace schema, so there is nothing to change on a
+        // database built from these migrations alone.
+        if !manager.has_table("WhiteListAddress").await? {
+            return Ok(());
+        }
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"ALTER TABLE "WhiteListAddress"
+                    DROP CONSTRAINT "WhiteListAddress_pkey",
+                    ADD CONSTRAINT "WhiteListAddress_pkey" PRIMARY KEY ("launchId", "address")"#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if !manager.has_table("WhiteListAddress").await? {
+            return Ok(());
+        }
+        // Fails while an address is whitelisted for more than one launch
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"ALTER TABLE "WhiteListAddress"
+                    DROP CONSTRAINT "WhiteListAddress_pkey",
+                    ADD CONSTRAINT "WhiteListAddress_pkey" PRIMARY KEY ("address")"#,
+            )
+            .await?;
+
+        Ok(())
+    }
+}
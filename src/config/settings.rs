@@ -0,0 +1,543 @@
+use std::fmt::Display;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::{Context, Error, anyhow, bail};
+use serde::Deserialize;
+
+use crate::constants::contract::WHITE_LISTED_PACKAGES;
+use crate::source::retry::RetryPolicy;
+
+/// File read when `INDEXER_CONFIG` is not set. A missing default file means "use defaults".
+const DEFAULT_CONFIG_PATH: &str = "indexer.toml";
+// Sui full nodes reject multi-get and paginated queries above this size
+const MAX_RPC_QUERY_LIMIT: usize = 50;
+// Public full node. Keyed provider URLs belong in `rpc.endpoints` / `INDEXER_RPC_ENDPOINTS`.
+const MAINNET_URL: &str = "https://fullnode.mainnet.sui.io:443";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Network {
+    Mainnet,
+    Testnet,
+    Devnet,
+    Localnet,
+}
+
+impl Network {
+    /// Endpoint used when `rpc.endpoints` is left empty.
+    fn default_endpoint(self) -> &'static str {
+        match self {
+            Network::Mainnet => MAINNET_URL,
+            Network::Testnet => "https://fullnode.testnet.sui.io:443",
+            Network::Devnet => "https://fullnode.devnet.sui.io:443",
+            Network::Localnet => "http://127.0.0.1:9000",
+        }
+    }
+}
+
+impl FromStr for Network {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "mainnet" => Ok(Network::Mainnet),
+            "testnet" => Ok(Network::Testnet),
+            "devnet" => Ok(Network::Devnet),
+            "localnet" => Ok(Network::Localnet),
+            _ => bail!("unknown network `{value}`, expected mainnet, testnet, devnet or localnet"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RpcConfig {
+    /// Providers in order of preference. Empty means the network's default endpoint.
+    pub endpoints: Vec<String>,
+    /// Client-side rate limit applied per endpoint.
+    pub requests_per_second: u32,
+    pub max_attempts: u32,
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    pub timeout_ms: u64,
+}
+
+impl Default for RpcConfig {
+    fn default() -> Self {
+        let retry = RetryPolicy::default();
+        Self {
+            endpoints: Vec::new(),
+            requests_per_second: 25,
+            max_attempts: retry.max_attempts,
+            initial_backoff_ms: retry.initial_backoff.as_millis() as u64,
+            max_backoff_ms: retry.max_backoff.as_millis() as u64,
+            timeout_ms: retry.timeout.as_millis() as u64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BatchConfig {
+    /// Checkpoints requested per `get_checkpoints` call.
+    pub checkpoints: usize,
+    /// Digests per `multi_get_transactions_with_options` call.
+    pub transactions: usize,
+    /// Object IDs per `multi_get_object_with_options` call.
+    pub objects: usize,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        Self {
+            checkpoints: 50,
+            transactions: MAX_RPC_QUERY_LIMIT,
+            objects: MAX_RPC_QUERY_LIMIT,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConcurrencyConfig {
+    /// Object batches requested at the same time while looking for NFTs.
+    pub object_queries: usize,
+}
+
+impl Default for ConcurrencyConfig {
+    fn default() -> Self {
+        Self { object_queries: 8 }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PipelineConfig {
+    /// Capacity of each channel between stages. Full channels slow down checkpoint fetching.
+    pub channel_capacity: usize,
+    pub filter_workers: usize,
+    pub assemble_workers: usize,
+    pub store_workers: usize,
+    /// How long in-flight checkpoints may take to finish after a shutdown signal.
+    pub shutdown_timeout_ms: u64,
+    /// How often the 24 hour and 7 day volume of every collection is re-summed.
+    pub stats_refresh_interval_ms: u64,
+}
+
+impl PipelineConfig {
+    pub fn shutdown_timeout(&self) -> Duration {
+        Duration::from_millis(self.shutdown_timeout_ms)
+    }
+
+    pub fn stats_refresh_interval(&self) -> Duration {
+        Duration::from_millis(self.stats_refresh_interval_ms)
+    }
+}
+
+impl Default for PipelineConfig {
+    fn default() -> Self {
+        Self {
+            channel_capacity: 16,
+            filter_workers: 4,
+            assemble_workers: 4,
+            store_workers: 2,
+            shutdown_timeout_ms: 30_000,
+            stats_refresh_interval_ms: 300_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TipConfig {
+    /// Poll interval right after the chain head is reached.
+    pub min_poll_interval_ms: u64,
+    /// Upper bound the interval backs off to while no new checkpoints appear.
+    pub max_poll_interval_ms: u64,
+    /// How often the lag behind the latest network checkpoint is printed.
+    pub lag_report_interval_ms: u64,
+}
+
+impl TipConfig {
+    pub fn min_poll_interval(&self) -> Duration {
+        Duration::from_millis(self.min_poll_interval_ms)
+    }
+
+    pub fn max_poll_interval(&self) -> Duration {
+        Duration::from_millis(self.max_poll_interval_ms)
+    }
+
+    pub fn lag_report_interval(&self) -> Duration {
+        Duration::from_millis(self.lag_report_interval_ms)
+    }
+}
+
+impl Default for TipConfig {
+    fn default() -> Self {
+        Self {
+            min_poll_interval_ms: 250,
+            max_poll_interval_ms: 5_000,
+            lag_report_interval_ms: 30_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CollectionsConfig {
+    /// Fully qualified NFT types (`0x<package>::<module>::<Name>`) that are indexed.
+    pub tracked: Vec<String>,
+}
+
+impl Default for CollectionsConfig {
+    fn default() -> Self {
+        Self {
+            tracked: WHITE_LISTED_PACKAGES
+                .iter()
+                .map(|package| package.to_string())
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub network: Network,
+    /// Cursor used when the live pipeline has no persisted progress yet.
+    pub start_checkpoint: u64,
+    pub rpc: RpcConfig,
+    pub batch: BatchConfig,
+    pub concurrency: ConcurrencyConfig,
+    pub pipeline: PipelineConfig,
+    pub tip: TipConfig,
+    pub collections: CollectionsConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            network: Network::Mainnet,
+            start_checkpoint: 193710192,
+            rpc: RpcConfig::default(),
+            batch: BatchConfig::default(),
+            concurrency: ConcurrencyConfig::default(),
+            pipeline: PipelineConfig::default(),
+            tip: TipConfig::default(),
+            collections: CollectionsConfig::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Loads `INDEXER_CONFIG` (or `indexer.toml` when present), applies `INDEXER_*`
+    /// environment overrides and validates the result.
+    pub fn load() -> Result<Self, Error> {
+        let mut config = match std::env::var("INDEXER_CONFIG") {
+            Ok(path) => Self::from_file(Path::new(&path))?,
+            Err(_) if Path::new(DEFAULT_CONFIG_PATH).exists() => {
+                Self::from_file(Path::new(DEFAULT_CONFIG_PATH))?
+            }
+            Err(_) => Self::default(),
+        };
+        config.apply_env(|name| std::env::var(name).ok())?;
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, Error> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Reading config file {}", path.display()))?;
+        Self::from_toml(&contents)
+            .with_context(|| format!("Parsing config file {}", path.display()))
+    }
+
+    pub fn from_toml(contents: &str) -> Result<Self, Error> {
+        Ok(toml::from_str(contents)?)
+    }
+
+    /// Overrides settings with the `INDEXER_*` variables returned by `var`.
+    /// List values are comma separated.
+    pub fn apply_env(&mut self, var: impl Fn(&str) -> Option<String>) -> Result<(), Error> {
+        for legacy in ["RPC_URLS", "API_URL"] {
+            if var(legacy).is_some() && var("INDEXER_RPC_ENDPOINTS").is_none() {
+                eprintln!("{legacy} is no longer read, set INDEXER_RPC_ENDPOINTS instead");
+            }
+        }
+        if let Some(value) = var("INDEXER_NETWORK") {
+            self.network = parse_var("INDEXER_NETWORK", &value)?;
+        }
+        if let Some(value) = var("INDEXER_START_CHECKPOINT") {
+            self.start_checkpoint = parse_var("INDEXER_START_CHECKPOINT", &value)?;
+        }
+        if let Some(value) = var("INDEXER_RPC_ENDPOINTS") {
+            self.rpc.endpoints = split_list(&value);
+        }
+        if let Some(value) = var("INDEXER_RPC_REQUESTS_PER_SECOND") {
+            self.rpc.requests_per_second = parse_var("INDEXER_RPC_REQUESTS_PER_SECOND", &value)?;
+        }
+        if let Some(value) = var("INDEXER_RPC_MAX_ATTEMPTS") {
+            self.rpc.max_attempts = parse_var("INDEXER_RPC_MAX_ATTEMPTS", &value)?;
+        }
+        if let Some(value) = var("INDEXER_RPC_INITIAL_BACKOFF_MS") {
+            self.rpc.initial_backoff_ms = parse_var("INDEXER_RPC_INITIAL_BACKOFF_MS", &value)?;
+        }
+        if let Some(value) = var("INDEXER_RPC_MAX_BACKOFF_MS") {
+            self.rpc.max_backoff_ms = parse_var("INDEXER_RPC_MAX_BACKOFF_MS", &value)?;
+        }
+        if let Some(value) = var("INDEXER_RPC_TIMEOUT_MS") {
+            self.rpc.timeout_ms = parse_var("INDEXER_RPC_TIMEOUT_MS", &value)?;
+        }
+        if let Some(value) = var("INDEXER_BATCH_CHECKPOINTS") {
+            self.batch.checkpoints = parse_var("INDEXER_BATCH_CHECKPOINTS", &value)?;
+        }
+        if let Some(value) = var("INDEXER_BATCH_TRANSACTIONS") {
+            self.batch.transactions = parse_var("INDEXER_BATCH_TRANSACTIONS", &value)?;
+        }
+        if let Some(value) = var("INDEXER_BATCH_OBJECTS") {
+            self.batch.objects = parse_var("INDEXER_BATCH_OBJECTS", &value)?;
+        }
+        if let Some(value) = var("INDEXER_CONCURRENCY_OBJECT_QUERIES") {
+            self.concurrency.object_queries =
+                parse_var("INDEXER_CONCURRENCY_OBJECT_QUERIES", &value)?;
+        }
+        if let Some(value) = var("INDEXER_PIPELINE_CHANNEL_CAPACITY") {
+            self.pipeline.channel_capacity =
+                parse_var("INDEXER_PIPELINE_CHANNEL_CAPACITY", &value)?;
+        }
+        if let Some(value) = var("INDEXER_PIPELINE_FILTER_WORKERS") {
+            self.pipeline.filter_workers = parse_var("INDEXER_PIPELINE_FILTER_WORKERS", &value)?;
+        }
+        if let Some(value) = var("INDEXER_PIPELINE_ASSEMBLE_WORKERS") {
+            self.pipeline.assemble_workers =
+                parse_var("INDEXER_PIPELINE_ASSEMBLE_WORKERS", &value)?;
+        }
+        if let Some(value) = var("INDEXER_PIPELINE_STORE_WORKERS") {
+            self.pipeline.store_workers = parse_var("INDEXER_PIPELINE_STORE_WORKERS", &value)?;
+        }
+        if let Some(value) = var("INDEXER_PIPELINE_SHUTDOWN_TIMEOUT_MS") {
+            self.pipeline.shutdown_timeout_ms =
+                parse_var("INDEXER_PIPELINE_SHUTDOWN_TIMEOUT_MS", &value)?;
+        }
+        if let Some(value) = var("INDEXER_PIPELINE_STATS_REFRESH_INTERVAL_MS") {
+            self.pipeline.stats_refresh_interval_ms =
+                parse_var("INDEXER_PIPELINE_STATS_REFRESH_INTERVAL_MS", &value)?;
+        }
+        if let Some(value) = var("INDEXER_TIP_MIN_POLL_INTERVAL_MS") {
+            self.tip.min_poll_interval_ms = parse_var("INDEXER_TIP_MIN_POLL_INTERVAL_MS", &value)?;
+        }
+        if let Some(value) = var("INDEXER_TIP_MAX_POLL_INTERVAL_MS") {
+            self.tip.max_poll_interval_ms = parse_var("INDEXER_TIP_MAX_POLL_INTERVAL_MS", &value)?;
+        }
+        if let Some(value) = var("INDEXER_TIP_LAG_REPORT_INTERVAL_MS") {
+            self.tip.lag_report_interval_ms =
+                parse_var("INDEXER_TIP_LAG_REPORT_INTERVAL_MS", &value)?;
+        }
+        if let Some(value) = var("INDEXER_TRACKED_COLLECTIONS") {
+            self.collections.tracked = split_list(&value);
+        }
+
+        Ok(())
+    }
+
+    /// Reports every invalid setting at once.
+    pub fn validate(&self) -> Result<(), Error> {
+        let mut problems = Vec::new();
+
+        for endpoint in &self.rpc.endpoints {
+            if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+                problems.push(format!("rpc.endpoints: `{endpoint}` is not an http(s) URL"));
+            }
+        }
+        if self.rpc.requests_per_second == 0 {
+            problems.push("rpc.requests_per_second must be at least 1".to_string());
+        }
+        if self.rpc.max_attempts == 0 {
+            problems.push("rpc.max_attempts must be at least 1".to_string());
+        }
+        if self.rpc.timeout_ms == 0 {
+            problems.push("rpc.timeout_ms must be at least 1".to_string());
+        }
+        if self.rpc.initial_backoff_ms > self.rpc.max_backoff_ms {
+            problems.push("rpc.initial_backoff_ms must not exceed rpc.max_backoff_ms".to_string());
+        }
+        for (name, size) in [
+            ("batch.checkpoints", self.batch.checkpoints),
+            ("batch.transactions", self.batch.transactions),
+            ("batch.objects", self.batch.objects),
+        ] {
+            if size == 0 || size > MAX_RPC_QUERY_LIMIT {
+                problems.push(format!(
+                    "{name} must be between 1 and {MAX_RPC_QUERY_LIMIT}, got {size}"
+                ));
+            }
+        }
+        for (name, value) in [
+            (
+                "concurrency.object_queries",
+                self.concurrency.object_queries,
+            ),
+            ("pipeline.channel_capacity", self.pipeline.channel_capacity),
+            ("pipeline.filter_workers", self.pipeline.filter_workers),
+            ("pipeline.assemble_workers", self.pipeline.assemble_workers),
+            ("pipeline.store_workers", self.pipeline.store_workers),
+        ] {
+            if value == 0 {
+                problems.push(format!("{name} must be at least 1"));
+            }
+        }
+        if self.pipeline.stats_refresh_interval_ms == 0 {
+            problems.push("pipeline.stats_refresh_interval_ms must be at least 1".to_string());
+        }
+        if self.tip.min_poll_interval_ms == 0 {
+            problems.push("tip.min_poll_interval_ms must be at least 1".to_string());
+        }
+        if self.tip.max_poll_interval_ms < self.tip.min_poll_interval_ms {
+            problems.push(format!(
+                "tip.max_poll_interval_ms ({}) must not be below tip.min_poll_interval_ms ({})",
+                self.tip.max_poll_interval_ms, self.tip.min_poll_interval_ms
+            ));
+        }
+        for collection in &self.collections.tracked {
+            if !collection.starts_with("0x") || collection.split("::").count() < 3 {
+                problems.push(format!(
+                    "collections.tracked: `{collection}` is not a `0x<package>::<module>::<Name>` type"
+                ));
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "Invalid configuration:\n  - {}",
+                problems.join("\n  - ")
+            ))
+        }
+    }
+
+    /// Configured endpoints, or the network default when none are set.
+    pub fn rpc_endpoints(&self) -> Vec<String> {
+        if self.rpc.endpoints.is_empty() {
+            vec![self.network.default_endpoint().to_string()]
+        } else {
+            self.rpc.endpoints.clone()
+        }
+    }
+
+    pub fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: self.rpc.max_attempts,
+            initial_backoff: Duration::from_millis(self.rpc.initial_backoff_ms),
+            max_backoff: Duration::from_millis(self.rpc.max_backoff_ms),
+            timeout: Duration::from_millis(self.rpc.timeout_ms),
+        }
+    }
+}
+
+fn parse_var<T>(name: &str, value: &str) -> Result<T, Error>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    value
+        .trim()
+        .parse()
+        .map_err(|e| anyhow!("Invalid value `{value}` for {name}: {e}"))
+}
+
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn test_defaults_are_valid() {
+        let config = Config::default();
+        config.validate().unwrap();
+        assert_eq!(config.rpc_endpoints(), vec![MAINNET_URL.to_string()]);
+        assert_eq!(
+            config.collections.tracked.len(),
+            WHITE_LISTED_PACKAGES.len()
+        );
+    }
+
+    #[test]
+    fn test_from_toml_with_env_overrides() {
+        let mut config = Config::from_toml(
+            r#"
+            network = "testnet"
+            start_checkpoint = 100
+
+            [rpc]
+            endpoints = ["https://a.example", "https://b.example"]
+
+            [batch]
+            objects = 25
+
+            [collections]
+            tracked = ["0x2::kiosk::Item"]
+            "#,
+        )
+        .unwrap();
+
+        let env = HashMap::from([
+            ("INDEXER_START_CHECKPOINT", "250"),
+            ("INDEXER_CONCURRENCY_OBJECT_QUERIES", "2"),
+            ("INDEXER_RPC_INITIAL_BACKOFF_MS", "50"),
+            ("INDEXER_RPC_MAX_BACKOFF_MS", "2000"),
+        ]);
+        config
+            .apply_env(|name| env.get(name).map(|value| value.to_string()))
+            .unwrap();
+        config.validate().unwrap();
+
+        assert_eq!(config.network, Network::Testnet);
+        assert_eq!(config.start_checkpoint, 250);
+        assert_eq!(config.rpc.endpoints.len(), 2);
+        assert_eq!(config.batch.objects, 25);
+        assert_eq!(config.batch.transactions, MAX_RPC_QUERY_LIMIT);
+        assert_eq!(config.concurrency.object_queries, 2);
+        assert_eq!(config.rpc.initial_backoff_ms, 50);
+        assert_eq!(config.rpc.max_backoff_ms, 2000);
+        assert_eq!(config.collections.tracked, vec!["0x2::kiosk::Item"]);
+    }
+
+    #[test]
+    fn test_validate_reports_every_problem() {
+        let mut config = Config::default();
+        config.rpc.endpoints = vec!["ws://node".to_string()];
+        config.batch.objects = 500;
+        config.concurrency.object_queries = 0;
+        config.tip.max_poll_interval_ms = 10;
+
+        let message = config.validate().unwrap_err().to_string();
+        assert!(message.contains("rpc.endpoints"));
+        assert!(message.contains("batch.objects"));
+        assert!(message.contains("concurrency.object_queries"));
+        assert!(message.contains("tip.max_poll_interval_ms"));
+    }
+
+    #[test]
+    fn test_rejects_unknown_keys_and_bad_env() {
+        assert!(Config::from_toml("unknown = 1").is_err());
+
+        let mut config = Config::default();
+        let result = config
+            .apply_env(|name| (name == "INDEXER_START_CHECKPOINT").then(|| "latest".to_string()));
+        assert!(result.is_err());
+    }
+}
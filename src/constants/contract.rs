@@ -24,115 +24,110 @@ impl HokkoPackageId {
     }
 }
 
+/// Published versions of the Hokko marketplace package. Every version emits the same
+/// event names, so marketplace event types are built per version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarketplaceVersion {
+    V1,
+    V2,
+    V3,
+}
+
+impl MarketplaceVersion {
+    pub const ALL: [MarketplaceVersion; 3] = [
+        MarketplaceVersion::V1,
+        MarketplaceVersion::V2,
+        MarketplaceVersion::V3,
+    ];
+
+    pub fn package_id(self) -> &'static str {
+        match self {
+            MarketplaceVersion::V1 => HokkoPackageId::MARKETPLACE_V1,
+            MarketplaceVersion::V2 => HokkoPackageId::MARKETPLACE_V2,
+            MarketplaceVersion::V3 => HokkoPackageId::MARKETPLACE_V3,
+        }
+    }
+}
+
 pub struct HokkoEventTypes;
 impl HokkoEventTypes {
-    pub fn kiosk_created() -> String {
-        format!(
-            "{}::marketplace::KioskCreatedEvent",
-            HokkoPackageId::MARKETPLACE_V1
-        )
+    pub fn kiosk_created(version: MarketplaceVersion) -> String {
+        format!("{}::marketplace::KioskCreatedEvent", version.package_id())
     }
 
-    pub fn listing_created() -> String {
-        format!("{}::trade::ItemListedEvent", HokkoPackageId::MARKETPLACE_V1)
+    pub fn listing_created(version: MarketplaceVersion) -> String {
+        format!("{}::trade::ItemListedEvent", version.package_id())
     }
 
-    pub fn listing_updated() -> String {
-        format!(
-            "{}::trade::ItemUpdatedEvent",
-            HokkoPackageId::MARKETPLACE_V1
-        )
+    pub fn listing_updated(version: MarketplaceVersion) -> String {
+        format!("{}::trade::ItemUpdatedEvent", version.package_id())
     }
 
-    pub fn delisted() -> String {
-        format!(
-            "{}::trade::ItemDelistedEvent",
-            HokkoPackageId::MARKETPLACE_V1
-        )
+    pub fn delisted(version: MarketplaceVersion) -> String {
+        format!("{}::trade::ItemDelistedEvent", version.package_id())
     }
 
-    pub fn purchased() -> String {
-        format!("{}::trade::ItemBoughtEvent", HokkoPackageId::MARKETPLACE_V1)
+    pub fn purchased(version: MarketplaceVersion) -> String {
+        format!("{}::trade::ItemBoughtEvent", version.package_id())
     }
 
-    pub fn offer_created() -> String {
-        format!("{}::escrow::OfferEvent", HokkoPackageId::MARKETPLACE_V1)
+    pub fn offer_created(version: MarketplaceVersion) -> String {
+        format!("{}::escrow::OfferEvent", version.package_id())
     }
 
-    pub fn offer_accepted() -> String {
-        format!(
-            "{}::escrow::AcceptOfferEvent",
-            HokkoPackageId::MARKETPLACE_V1
-        )
+    pub fn offer_accepted(version: MarketplaceVersion) -> String {
+        format!("{}::escrow::AcceptOfferEvent", version.package_id())
     }
 
-    pub fn offer_declined() -> String {
-        format!(
-            "{}::escrow::DeclineOfferEvent",
-            HokkoPackageId::MARKETPLACE_V1
-        )
+    pub fn offer_declined(version: MarketplaceVersion) -> String {
+        format!("{}::escrow::DeclineOfferEvent", version.package_id())
     }
 
-    pub fn offer_revoked() -> String {
-        format!(
-            "{}::escrow::RevokeOfferEvent",
-            HokkoPackageId::MARKETPLACE_V1
-        )
+    pub fn offer_revoked(version: MarketplaceVersion) -> String {
+        format!("{}::escrow::RevokeOfferEvent", version.package_id())
     }
 
-    pub fn collection_offer_created() -> String {
-        format!(
-            "{}::collection_escrow::NewOfferEvent",
-            HokkoPackageId::MARKETPLACE_V1
-        )
+    pub fn collection_offer_created(version: MarketplaceVersion) -> String {
+        format!("{}::collection_escrow::NewOfferEvent", version.package_id())
     }
 
-    pub fn collection_offer_accepted() -> String {
+    pub fn collection_offer_accepted(version: MarketplaceVersion) -> String {
         format!(
             "{}::collection_escrow::OfferAcceptedEvent",
-            HokkoPackageId::MARKETPLACE_V1
+            version.package_id()
         )
     }
 
-    pub fn collection_offer_revoked() -> String {
+    pub fn collection_offer_revoked(version: MarketplaceVersion) -> String {
         format!(
             "{}::collection_escrow::OfferRevokedEvent",
-            HokkoPackageId::MARKETPLACE_V1
+            version.package_id()
         )
     }
 
-    pub fn personal_fee_updated() -> String {
-        format!(
-            "{}::marketplace::PersonalFeeSetEvent",
-            HokkoPackageId::MARKETPLACE_V1
-        )
+    pub fn personal_fee_updated(version: MarketplaceVersion) -> String {
+        format!("{}::marketplace::PersonalFeeSetEvent", version.package_id())
     }
 
-    pub fn recipient_created() -> String {
-        format!(
-            "{}::escrow::ReceiptCreatedEvent",
-            HokkoPackageId::MARKETPLACE_V1
-        )
+    pub fn recipient_created(version: MarketplaceVersion) -> String {
+        format!("{}::escrow::ReceiptCreatedEvent", version.package_id())
     }
 
-    pub fn recipient_destroyed() -> String {
-        format!(
-            "{}::escrow::ReceiptDestroyedEvent",
-            HokkoPackageId::MARKETPLACE_V1
-        )
+    pub fn recipient_destroyed(version: MarketplaceVersion) -> String {
+        format!("{}::escrow::ReceiptDestroyedEvent", version.package_id())
     }
 
-    pub fn recipient_created_collection() -> String {
+    pub fn recipient_created_collection(version: MarketplaceVersion) -> String {
         format!(
             "{}::collection_escrow::ReceiptCreatedEvent",
-            HokkoPackageId::MARKETPLACE_V1
+            version.package_id()
         )
     }
 
-    pub fn recipient_destroyed_collection() -> String {
+    pub fn recipient_destroyed_collection(version: MarketplaceVersion) -> String {
         format!(
             "{}::collection_escrow::ReceiptDestroyedEvent",
-            HokkoPackageId::MARKETPLACE_V1
+            version.package_id()
         )
     }
 
@@ -214,6 +209,23 @@ impl HokkoEventTypes {
     }
 }
 
+/// Events of the Sui framework kiosk. They are generic over the item type and are matched
+/// without their type parameter.
+pub struct KioskEventTypes;
+impl KioskEventTypes {
+    pub fn item_listed() -> String {
+        "0x2::kiosk::ItemListed".to_string()
+    }
+
+    pub fn item_purchased() -> String {
+        "0x2::kiosk::ItemPurchased".to_string()
+    }
+
+    pub fn item_delisted() -> String {
+        "0x2::kiosk::ItemDelisted".to_string()
+    }
+}
+
 pub const WHITE_LISTED_PACKAGES: &[&str] = &[
     "0x57191e5e5c41166b90a4b7811ad3ec7963708aa537a8438c1761a5d33e2155fd::kumo::Kumo",
     "0xd2197b1ce2096e96e726c29fa2c138c5c6748da169b81d34927c522b7499f1d7::ika_chan_nft::IkaChanNft",
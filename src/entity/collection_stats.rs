@@ -0,0 +1,22 @@
+//! `SeaORM` Entity for `collection_stats`. Written by hand, not generated: keep it in sync with
+//! the `m20251019_000001_create_collection_stats` migration.
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "collection_stats")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false, column_type = "Text")]
+    pub collection_type: String,
+    pub floor_price: Option<i64>,
+    pub listed_count: i64,
+    pub best_offer: Option<i64>,
+    pub volume_24h: i64,
+    pub volume_7d: i64,
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
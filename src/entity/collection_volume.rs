@@ -0,0 +1,19 @@
+//! `SeaORM` Entity for `collection_volume`. Written by hand, not generated: keep it in sync with
+//! the `m20251019_000001_create_collection_stats` migration.
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "collection_volume")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false, column_type = "Text")]
+    pub collection_type: String,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub hour: DateTime,
+    pub volume: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,18 @@
+//! `SeaORM` Entity for `indexer_state`. Written by hand, not generated: keep it in sync with
+//! the `m20251018_000001_create_indexer_state` migration.
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "indexer_state")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false, column_type = "Text")]
+    pub pipeline: String,
+    pub checkpoint: i64,
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
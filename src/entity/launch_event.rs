@@ -0,0 +1,23 @@
+//! `SeaORM` Entity for `launch_event`. Written by hand, not generated: keep it in sync with
+//! the `m20251020_000001_create_launch_event` migration.
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "launch_event")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false, column_type = "Text")]
+    pub digest: String,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub event_seq: i64,
+    #[sea_orm(column_type = "Text")]
+    pub launch_id: String,
+    #[sea_orm(column_type = "Text")]
+    pub kind: String,
+    pub created_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -4,8 +4,12 @@ pub mod prelude;
 
 pub mod collection;
 pub mod collection_offer;
+pub mod collection_stats;
+pub mod collection_volume;
 pub mod dynamic_nft_attribute;
+pub mod indexer_state;
 pub mod kiosk;
+pub mod launch_event;
 pub mod launchpad_collection;
 pub mod listing;
 pub mod nft;
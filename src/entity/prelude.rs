@@ -2,8 +2,12 @@
 
 pub use super::collection::Entity as Collection;
 pub use super::collection_offer::Entity as CollectionOffer;
+pub use super::collection_stats::Entity as CollectionStats;
+pub use super::collection_volume::Entity as CollectionVolume;
 pub use super::dynamic_nft_attribute::Entity as DynamicNftAttribute;
+pub use super::indexer_state::Entity as IndexerState;
 pub use super::kiosk::Entity as Kiosk;
+pub use super::launch_event::Entity as LaunchEvent;
 pub use super::launchpad_collection::Entity as LaunchpadCollection;
 pub use super::listing::Entity as Listing;
 pub use super::nft::Entity as Nft;
@@ -8,7 +8,12 @@ pub struct Model {
     #[sea_orm(primary_key, auto_increment = false, column_type = "Text")]
     pub address: String,
     pub allocation: i32,
-    #[sea_orm(column_name = "launchId", column_type = "Text")]
+    #[sea_orm(
+        column_name = "launchId",
+        primary_key,
+        auto_increment = false,
+        column_type = "Text"
+    )]
     pub launch_id: String,
     #[sea_orm(column_type = "Text", nullable)]
     pub name: Option<String>,
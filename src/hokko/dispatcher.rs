@@ -0,0 +1,168 @@
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::Mutex;
+
+use anyhow::{Context, Error};
+use futures::future::BoxFuture;
+use sui_sdk::rpc_types::SuiEvent;
+
+use crate::AppState;
+
+/// Everything a handler gets to know about one event.
+#[derive(Debug, Clone)]
+pub struct EventContext {
+    pub event: SuiEvent,
+    pub digest: String,
+    pub sender: String,
+    /// Timestamp of the checkpoint that included the transaction.
+    pub timestamp_ms: u64,
+    /// Whether the transaction also emitted Hokko events. Hokko trades go through the
+    /// framework kiosk, whose events then repeat what the Hokko events already record.
+    pub hokko_transaction: bool,
+}
+
+type Handler =
+    Box<dyn Fn(AppState, EventContext) -> BoxFuture<'static, Result<(), Error>> + Send + Sync>;
+
+/// Routes Hokko events to the handler registered for their fully qualified type. Generic
+/// events fall back to the handler registered for their type without type parameters.
+///
+/// The pipeline dispatches checkpoints one after another and events in emission order.
+/// Handlers must still tolerate seeing an event twice, since checkpoints past the committed
+/// cursor are indexed again after a restart, and backfills may run next to the live pipeline.
+pub struct Dispatcher {
+    packages: HashSet<String>,
+    handlers: HashMap<String, Handler>,
+    unknown: Mutex<HashMap<String, u64>>,
+}
+
+impl Dispatcher {
+    /// `packages` are the Hokko packages whose unhandled events are reported.
+    pub fn new(packages: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            packages: packages
+                .into_iter()
+                .map(|package| canonical_address(&package))
+                .collect(),
+            handlers: HashMap::new(),
+            unknown: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn register<F, Fut>(&mut self, event_type: String, handler: F)
+    where
+        F: Fn(AppState, EventContext) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), Error>> + Send + 'static,
+    {
+        self.handlers.insert(
+            canonical_type(&event_type),
+            Box::new(move |app_state, context| Box::pin(handler(app_state, context))),
+        );
+    }
+
+    /// Runs the handler for `context.event`. Events of other packages are ignored; Hokko
+    /// events without a handler are logged and counted.
+    pub async fn dispatch(&self, app_state: &AppState, context: EventContext) -> Result<(), Error> {
+        let event_type = canonical_type(&context.event.type_.to_string());
+
+        match self.handler_for(&event_type) {
+            Some(handler) => {
+                let digest = context.digest.clone();
+                handler(app_state.clone(), context)
+                    .await
+                    .with_context(|| format!("Handling {event_type} in {digest}"))
+            }
+            None => {
+                if self.is_hokko_package(&context.event.package_id.to_string()) {
+                    let seen = self.record_unknown(&event_type);
+                    eprintln!(
+                        "Unhandled Hokko event {event_type} in {} (seen {seen} times)",
+                        context.digest
+                    );
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn handler_for(&self, event_type: &str) -> Option<&Handler> {
+        self.handlers.get(event_type).or_else(|| {
+            event_type
+                .split_once('<')
+                .and_then(|(base_type, _)| self.handlers.get(base_type))
+        })
+    }
+
+    pub fn is_hokko_package(&self, package: &str) -> bool {
+        self.packages.contains(&canonical_address(package))
+    }
+
+    /// Total number of unhandled Hokko events seen so far.
+    pub fn unknown_count(&self) -> u64 {
+        self.unknown.lock().unwrap().values().sum()
+    }
+
+    fn record_unknown(&self, event_type: &str) -> u64 {
+        let mut unknown = self.unknown.lock().unwrap();
+        let seen = unknown.entry(event_type.to_string()).or_insert(0);
+        *seen += 1;
+        *seen
+    }
+}
+
+/// Pads an address to 64 hex digits. Type tags print addresses without leading zeros while
+/// object IDs keep them, so both forms must compare equal.
+fn canonical_address(address: &str) -> String {
+    let hex = address.strip_prefix("0x").unwrap_or(address);
+    format!("0x{:0>64}", hex.to_lowercase())
+}
+
+/// Canonicalises the package address of a `<package>::<module>::<Name>` type string.
+pub fn canonical_type(event_type: &str) -> String {
+    match event_type.split_once("::") {
+        Some((address, rest)) => format!("{}::{rest}", canonical_address(address)),
+        None => event_type.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonical_type_pads_short_addresses() {
+        assert_eq!(
+            canonical_type("0x2::kiosk::ItemListed<0x3::nft::Nft>"),
+            format!("0x{}2::kiosk::ItemListed<0x3::nft::Nft>", "0".repeat(63))
+        );
+        assert_eq!(
+            canonical_type(
+                "0x392ebf946f80f1a93ba1a171ff286a88eb76f7c65d02e3639121b11f7e65be3::trade::ItemListedEvent"
+            ),
+            "0x0392ebf946f80f1a93ba1a171ff286a88eb76f7c65d02e3639121b11f7e65be3::trade::ItemListedEvent"
+        );
+    }
+
+    #[test]
+    fn test_generic_events_match_their_base_type() {
+        let mut dispatcher = Dispatcher::new(Vec::new());
+        dispatcher.register("0x2::kiosk::ItemListed".to_string(), |_, _| async {
+            Ok(())
+        });
+
+        let listed = canonical_type("0x2::kiosk::ItemListed<0x3::nft::Nft>");
+        assert!(dispatcher.handler_for(&listed).is_some());
+        let delisted = canonical_type("0x2::kiosk::ItemDelisted<0x3::nft::Nft>");
+        assert!(dispatcher.handler_for(&delisted).is_none());
+    }
+
+    #[test]
+    fn test_unknown_events_are_counted_per_type() {
+        let dispatcher = Dispatcher::new(["0x1".to_string()]);
+
+        assert_eq!(dispatcher.record_unknown("0x1::a::A"), 1);
+        assert_eq!(dispatcher.record_unknown("0x1::a::A"), 2);
+        assert_eq!(dispatcher.record_unknown("0x1::b::B"), 1);
+        assert_eq!(dispatcher.unknown_count(), 3);
+    }
+}
@@ -0,0 +1,51 @@
+//! Events of the Sui framework kiosk (`0x2::kiosk`). The item type is only part of the
+//! event type, as its type parameter.
+
+use serde::Deserialize;
+
+use super::move_u64;
+
+/// `0x2::kiosk::ItemListed<T>`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ItemListed {
+    pub kiosk: String,
+    pub id: String,
+    #[serde(deserialize_with = "move_u64")]
+    pub price: u64,
+}
+
+/// `0x2::kiosk::ItemPurchased<T>`. The buyer is the transaction sender.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ItemPurchased {
+    pub kiosk: String,
+    pub id: String,
+    #[serde(deserialize_with = "move_u64")]
+    pub price: u64,
+}
+
+/// `0x2::kiosk::ItemDelisted<T>`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ItemDelisted {
+    pub kiosk: String,
+    pub id: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::hokko::events::decode_json;
+
+    #[test]
+    fn test_decode_item_purchased() {
+        let event: ItemPurchased = decode_json(
+            "0x2::kiosk::ItemPurchased",
+            &json!({ "kiosk": "0xa1", "id": "0xa4", "price": "990000000" }),
+        )
+        .unwrap();
+
+        assert_eq!(event.id, "0xa4");
+        assert_eq!(event.price, 990_000_000);
+    }
+}
@@ -0,0 +1,184 @@
+//! Events emitted by the Hokko launchpad package (`launchpad` and `launch_manager`
+//! modules). Timestamps are Unix milliseconds, prices are in MIST.
+
+use serde::Deserialize;
+
+use super::{move_u64, type_name};
+
+/// `launchpad::LaunchpadApprovedEvent`, `LaunchpadRejectedEvent`, `LaunchpadPausedEvent`
+/// and `LaunchpadResumedEvent`, sent by Hokko admins. They only identify the launch.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LaunchpadAdminEvent {
+    pub launch_id: String,
+}
+
+/// `launch_manager::LaunchPausedEvent` and `LaunchResumedEvent`, sent by the creator.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LaunchCreatorEvent {
+    pub launch_id: String,
+    pub creator: String,
+}
+
+/// `launch_manager::LaunchInitializedEvent`, the creator registering a new collection.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LaunchInitializedEvent {
+    pub launch_id: String,
+    pub creator_cap: String,
+    #[serde(deserialize_with = "type_name")]
+    pub collection_type: String,
+    pub name: String,
+    pub description: String,
+    pub cover_image: Option<String>,
+    #[serde(deserialize_with = "move_u64")]
+    pub supply: u64,
+    #[serde(deserialize_with = "move_u64")]
+    pub price: u64,
+    #[serde(deserialize_with = "move_u64")]
+    pub max_item_per_address: u64,
+    pub white_list_enabled: bool,
+    #[serde(deserialize_with = "move_u64")]
+    pub white_list_price: u64,
+    #[serde(deserialize_with = "move_u64")]
+    pub white_list_supply: u64,
+    pub custom_enabled: bool,
+    pub custom_name: String,
+    #[serde(deserialize_with = "move_u64")]
+    pub custom_price: u64,
+    #[serde(deserialize_with = "move_u64")]
+    pub custom_supply: u64,
+    #[serde(deserialize_with = "move_u64")]
+    pub start_time: u64,
+    #[serde(deserialize_with = "move_u64")]
+    pub white_list_start_time: u64,
+    #[serde(deserialize_with = "move_u64")]
+    pub custom_start_time: u64,
+    pub kiosk_standard: bool,
+    pub native: bool,
+    pub creator: String,
+}
+
+/// `launch_manager::LaunchTimestampsUpdatedEvent`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LaunchTimestampsUpdatedEvent {
+    pub launch_id: String,
+    #[serde(deserialize_with = "move_u64")]
+    pub start_time: u64,
+    #[serde(deserialize_with = "move_u64")]
+    pub white_list_start_time: u64,
+    #[serde(deserialize_with = "move_u64")]
+    pub custom_start_time: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct WhitelistEntry {
+    pub address: String,
+    #[serde(deserialize_with = "move_u64")]
+    pub allocation: u64,
+}
+
+/// `launch_manager::LaunchWhitelistUpdatedEvent`. Entries in `added` replace any existing
+/// allocation for the same address.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LaunchWhitelistUpdatedEvent {
+    pub launch_id: String,
+    #[serde(default)]
+    pub added: Vec<WhitelistEntry>,
+    #[serde(default)]
+    pub removed: Vec<String>,
+}
+
+/// `launch_manager::ItemMintedEvent`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ItemMintedEvent {
+    pub launch_id: String,
+    pub nft_id: String,
+    #[serde(deserialize_with = "type_name")]
+    pub collection_type: String,
+    #[serde(deserialize_with = "move_u64")]
+    pub price: u64,
+    pub minter: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::hokko::events::decode_json;
+
+    #[test]
+    fn test_decode_launch_initialized() {
+        let event: LaunchInitializedEvent = decode_json(
+            "launch_manager::LaunchInitializedEvent",
+            &json!({
+                "launch_id": "0xe1",
+                "creator_cap": "0xe2",
+                "collection_type": { "name": "abc::nft::Nft" },
+                "name": "Sample",
+                "description": "A sample launch",
+                "cover_image": null,
+                "supply": "1000",
+                "price": "1000000000",
+                "max_item_per_address": "5",
+                "white_list_enabled": true,
+                "white_list_price": "500000000",
+                "white_list_supply": "200",
+                "custom_enabled": false,
+                "custom_name": "",
+                "custom_price": "0",
+                "custom_supply": "0",
+                "start_time": "1760000000000",
+                "white_list_start_time": "1759990000000",
+                "custom_start_time": "0",
+                "kiosk_standard": true,
+                "native": true,
+                "creator": "0xb1"
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(event.collection_type, "0xabc::nft::Nft");
+        assert_eq!(event.supply, 1000);
+        assert_eq!(event.cover_image, None);
+        assert_eq!(event.white_list_start_time, 1_759_990_000_000);
+    }
+
+    #[test]
+    fn test_decode_whitelist_update() {
+        let event: LaunchWhitelistUpdatedEvent = decode_json(
+            "launch_manager::LaunchWhitelistUpdatedEvent",
+            &json!({
+                "launch_id": "0xe1",
+                "added": [{ "address": "0xb1", "allocation": "3" }],
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            event.added,
+            vec![WhitelistEntry {
+                address: "0xb1".to_string(),
+                allocation: 3
+            }]
+        );
+        assert!(event.removed.is_empty());
+    }
+
+    #[test]
+    fn test_decode_item_minted() {
+        let event: ItemMintedEvent = decode_json(
+            "launch_manager::ItemMintedEvent",
+            &json!({
+                "launch_id": "0xe1",
+                "nft_id": "0xf1",
+                "collection_type": { "name": "abc::nft::Nft" },
+                "price": "1000000000",
+                "minter": "0xb2"
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(event.minter, "0xb2");
+        assert_eq!(event.price, 1_000_000_000);
+    }
+}
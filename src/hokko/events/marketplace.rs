@@ -0,0 +1,507 @@
+//! Events emitted by the Hokko marketplace package (`marketplace`, `trade`, `escrow` and
+//! `collection_escrow` modules). Prices and fees are in MIST.
+//!
+//! Structs without a version suffix are the current payloads. `V1` structs are the shapes
+//! of the first package, which predates non-kiosk listings and offers.
+
+use anyhow::Error;
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::{MarketplaceEvent, decode_with_v1, move_u64, type_name};
+
+/// `marketplace::KioskCreatedEvent`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct KioskCreatedEvent {
+    pub kiosk: String,
+    pub kiosk_owner_cap: String,
+    pub owner: String,
+    pub personal: bool,
+}
+
+/// `marketplace::PersonalFeeSetEvent`. The fee is in basis points.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PersonalFeeSetEvent {
+    pub owner: String,
+    #[serde(deserialize_with = "move_u64")]
+    pub fee: u64,
+}
+
+/// `trade::ItemListedEvent`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ItemListedEvent {
+    pub kiosk: String,
+    pub kiosk_owner_cap: String,
+    pub shared_purchase_cap: String,
+    pub token_id: String,
+    #[serde(deserialize_with = "type_name")]
+    pub collection_type: String,
+    #[serde(deserialize_with = "move_u64")]
+    pub price: u64,
+    #[serde(deserialize_with = "move_u64")]
+    pub marketplace_fee: u64,
+    #[serde(deserialize_with = "move_u64")]
+    pub royalty_fee: u64,
+    pub owner: String,
+    pub kiosk_standard: bool,
+}
+
+/// `trade::ItemListedEvent` of the first package version. Every listing was kiosk standard.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ItemListedEventV1 {
+    pub kiosk: String,
+    pub kiosk_owner_cap: String,
+    pub shared_purchase_cap: String,
+    pub token_id: String,
+    #[serde(deserialize_with = "type_name")]
+    pub collection_type: String,
+    #[serde(deserialize_with = "move_u64")]
+    pub price: u64,
+    #[serde(deserialize_with = "move_u64")]
+    pub marketplace_fee: u64,
+    #[serde(deserialize_with = "move_u64")]
+    pub royalty_fee: u64,
+    pub owner: String,
+}
+
+impl From<ItemListedEventV1> for ItemListedEvent {
+    fn from(event: ItemListedEventV1) -> Self {
+        Self {
+            kiosk: event.kiosk,
+            kiosk_owner_cap: event.kiosk_owner_cap,
+            shared_purchase_cap: event.shared_purchase_cap,
+            token_id: event.token_id,
+            collection_type: event.collection_type,
+            price: event.price,
+            marketplace_fee: event.marketplace_fee,
+            royalty_fee: event.royalty_fee,
+            owner: event.owner,
+            kiosk_standard: true,
+        }
+    }
+}
+
+/// `trade::ItemUpdatedEvent`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ItemUpdatedEvent {
+    pub kiosk: String,
+    pub shared_purchase_cap: String,
+    pub token_id: String,
+    #[serde(deserialize_with = "type_name")]
+    pub collection_type: String,
+    #[serde(deserialize_with = "move_u64")]
+    pub price: u64,
+    #[serde(deserialize_with = "move_u64")]
+    pub marketplace_fee: u64,
+    #[serde(deserialize_with = "move_u64")]
+    pub royalty_fee: u64,
+    pub owner: String,
+}
+
+/// `trade::ItemDelistedEvent`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ItemDelistedEvent {
+    pub kiosk: String,
+    pub shared_purchase_cap: String,
+    pub token_id: String,
+    #[serde(deserialize_with = "type_name")]
+    pub collection_type: String,
+    pub owner: String,
+}
+
+/// `trade::ItemBoughtEvent`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ItemBoughtEvent {
+    pub kiosk: String,
+    pub shared_purchase_cap: String,
+    pub token_id: String,
+    #[serde(deserialize_with = "type_name")]
+    pub collection_type: String,
+    #[serde(deserialize_with = "move_u64")]
+    pub price: u64,
+    #[serde(deserialize_with = "move_u64")]
+    pub marketplace_fee: u64,
+    #[serde(deserialize_with = "move_u64")]
+    pub royalty_fee: u64,
+    pub seller: String,
+    pub buyer: String,
+}
+
+/// `escrow::OfferEvent`, emitted when an offer on a single NFT is placed.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct OfferEvent {
+    pub offer_id: String,
+    pub offer_cap: String,
+    pub kiosk: String,
+    pub token_id: String,
+    #[serde(deserialize_with = "type_name")]
+    pub collection_type: String,
+    #[serde(deserialize_with = "move_u64")]
+    pub price: u64,
+    #[serde(deserialize_with = "move_u64")]
+    pub marketplace_fee: u64,
+    #[serde(deserialize_with = "move_u64")]
+    pub royalty_fee: u64,
+    pub owner: String,
+    pub kiosk_standard: bool,
+}
+
+/// `escrow::OfferEvent` of the first package version.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct OfferEventV1 {
+    pub offer_id: String,
+    pub offer_cap: String,
+    pub kiosk: String,
+    pub token_id: String,
+    #[serde(deserialize_with = "type_name")]
+    pub collection_type: String,
+    #[serde(deserialize_with = "move_u64")]
+    pub price: u64,
+    #[serde(deserialize_with = "move_u64")]
+    pub marketplace_fee: u64,
+    #[serde(deserialize_with = "move_u64")]
+    pub royalty_fee: u64,
+    pub owner: String,
+}
+
+impl From<OfferEventV1> for OfferEvent {
+    fn from(event: OfferEventV1) -> Self {
+        Self {
+            offer_id: event.offer_id,
+            offer_cap: event.offer_cap,
+            kiosk: event.kiosk,
+            token_id: event.token_id,
+            collection_type: event.collection_type,
+            price: event.price,
+            marketplace_fee: event.marketplace_fee,
+            royalty_fee: event.royalty_fee,
+            owner: event.owner,
+            kiosk_standard: true,
+        }
+    }
+}
+
+/// `escrow::AcceptOfferEvent`. The seller receives the escrowed funds, the buyer the NFT.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AcceptOfferEvent {
+    pub offer_id: String,
+    pub kiosk: String,
+    pub token_id: String,
+    #[serde(deserialize_with = "type_name")]
+    pub collection_type: String,
+    #[serde(deserialize_with = "move_u64")]
+    pub price: u64,
+    #[serde(deserialize_with = "move_u64")]
+    pub marketplace_fee: u64,
+    #[serde(deserialize_with = "move_u64")]
+    pub royalty_fee: u64,
+    pub seller: String,
+    pub buyer: String,
+}
+
+/// `escrow::DeclineOfferEvent`, sent by the NFT owner.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DeclineOfferEvent {
+    pub offer_id: String,
+    pub token_id: String,
+    #[serde(deserialize_with = "type_name")]
+    pub collection_type: String,
+    pub owner: String,
+}
+
+/// `escrow::RevokeOfferEvent`, sent by the offer maker.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RevokeOfferEvent {
+    pub offer_id: String,
+    pub token_id: String,
+    #[serde(deserialize_with = "type_name")]
+    pub collection_type: String,
+    pub owner: String,
+}
+
+/// `escrow::ReceiptCreatedEvent` and `collection_escrow::ReceiptCreatedEvent`. A receipt
+/// holds the proceeds of an accepted offer until its owner claims them.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ReceiptCreatedEvent {
+    pub receipt_id: String,
+    pub offer_id: String,
+    pub token_id: String,
+    #[serde(deserialize_with = "type_name")]
+    pub collection_type: String,
+    pub owner: String,
+}
+
+/// `escrow::ReceiptDestroyedEvent` and `collection_escrow::ReceiptDestroyedEvent`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ReceiptDestroyedEvent {
+    pub receipt_id: String,
+    pub offer_id: String,
+    pub token_id: String,
+    #[serde(deserialize_with = "type_name")]
+    pub collection_type: String,
+    pub owner: String,
+}
+
+/// `collection_escrow::NewOfferEvent`, a bid on any NFT of a collection. `quantity` NFTs
+/// can be bought with it before it is used up.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct NewOfferEvent {
+    pub offer_id: String,
+    pub offer_cap: String,
+    pub kiosk: String,
+    pub policy_id: String,
+    #[serde(deserialize_with = "type_name")]
+    pub collection_type: String,
+    #[serde(deserialize_with = "move_u64")]
+    pub price: u64,
+    #[serde(deserialize_with = "move_u64")]
+    pub marketplace_fee: u64,
+    #[serde(deserialize_with = "move_u64")]
+    pub royalty_fee: u64,
+    #[serde(deserialize_with = "move_u64")]
+    pub quantity: u64,
+    pub owner: String,
+    pub kiosk_standard: bool,
+}
+
+/// `collection_escrow::NewOfferEvent` of the first package version.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct NewOfferEventV1 {
+    pub offer_id: String,
+    pub offer_cap: String,
+    pub kiosk: String,
+    pub policy_id: String,
+    #[serde(deserialize_with = "type_name")]
+    pub collection_type: String,
+    #[serde(deserialize_with = "move_u64")]
+    pub price: u64,
+    #[serde(deserialize_with = "move_u64")]
+    pub marketplace_fee: u64,
+    #[serde(deserialize_with = "move_u64")]
+    pub royalty_fee: u64,
+    #[serde(deserialize_with = "move_u64")]
+    pub quantity: u64,
+    pub owner: String,
+}
+
+impl From<NewOfferEventV1> for NewOfferEvent {
+    fn from(event: NewOfferEventV1) -> Self {
+        Self {
+            offer_id: event.offer_id,
+            offer_cap: event.offer_cap,
+            kiosk: event.kiosk,
+            policy_id: event.policy_id,
+            collection_type: event.collection_type,
+            price: event.price,
+            marketplace_fee: event.marketplace_fee,
+            royalty_fee: event.royalty_fee,
+            quantity: event.quantity,
+            owner: event.owner,
+            kiosk_standard: true,
+        }
+    }
+}
+
+/// `collection_escrow::OfferAcceptedEvent`, one fill of a collection offer. The offer stays
+/// open while `remaining` is above zero.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct OfferAcceptedEvent {
+    pub offer_id: String,
+    pub kiosk: String,
+    pub token_id: String,
+    #[serde(deserialize_with = "type_name")]
+    pub collection_type: String,
+    #[serde(deserialize_with = "move_u64")]
+    pub price: u64,
+    #[serde(deserialize_with = "move_u64")]
+    pub marketplace_fee: u64,
+    #[serde(deserialize_with = "move_u64")]
+    pub royalty_fee: u64,
+    #[serde(deserialize_with = "move_u64")]
+    pub remaining: u64,
+    pub seller: String,
+    pub buyer: String,
+}
+
+/// `collection_escrow::OfferRevokedEvent`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct OfferRevokedEvent {
+    pub offer_id: String,
+    #[serde(deserialize_with = "type_name")]
+    pub collection_type: String,
+    pub owner: String,
+}
+
+impl MarketplaceEvent for KioskCreatedEvent {}
+
+impl MarketplaceEvent for PersonalFeeSetEvent {}
+
+impl MarketplaceEvent for ItemListedEvent {
+    fn decode_payload(event_type: &str, parsed_json: &Value) -> Result<Self, Error> {
+        decode_with_v1::<Self, ItemListedEventV1>(event_type, parsed_json)
+    }
+}
+
+impl MarketplaceEvent for ItemUpdatedEvent {}
+
+impl MarketplaceEvent for ItemDelistedEvent {}
+
+impl MarketplaceEvent for ItemBoughtEvent {}
+
+impl MarketplaceEvent for OfferEvent {
+    fn decode_payload(event_type: &str, parsed_json: &Value) -> Result<Self, Error> {
+        decode_with_v1::<Self, OfferEventV1>(event_type, parsed_json)
+    }
+}
+
+impl MarketplaceEvent for AcceptOfferEvent {}
+
+impl MarketplaceEvent for DeclineOfferEvent {}
+
+impl MarketplaceEvent for RevokeOfferEvent {}
+
+impl MarketplaceEvent for ReceiptCreatedEvent {}
+
+impl MarketplaceEvent for ReceiptDestroyedEvent {}
+
+impl MarketplaceEvent for NewOfferEvent {
+    fn decode_payload(event_type: &str, parsed_json: &Value) -> Result<Self, Error> {
+        decode_with_v1::<Self, NewOfferEventV1>(event_type, parsed_json)
+    }
+}
+
+impl MarketplaceEvent for OfferAcceptedEvent {}
+
+impl MarketplaceEvent for OfferRevokedEvent {}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::hokko::events::decode_json;
+
+    const KUMO: &str =
+        "57191e5e5c41166b90a4b7811ad3ec7963708aa537a8438c1761a5d33e2155fd::kumo::Kumo";
+
+    #[test]
+    fn test_decode_item_listed() {
+        let event: ItemListedEvent = decode_json(
+            "trade::ItemListedEvent",
+            &json!({
+                "kiosk": "0xa1",
+                "kiosk_owner_cap": "0xa2",
+                "shared_purchase_cap": "0xa3",
+                "token_id": "0xa4",
+                "collection_type": { "name": KUMO },
+                "price": "2500000000",
+                "marketplace_fee": "50000000",
+                "royalty_fee": "125000000",
+                "owner": "0xb1",
+                "kiosk_standard": true
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(event.collection_type, format!("0x{KUMO}"));
+        assert_eq!(event.price, 2_500_000_000);
+        assert_eq!(event.marketplace_fee, 50_000_000);
+        assert!(event.kiosk_standard);
+    }
+
+    #[test]
+    fn test_decode_item_bought() {
+        let event: ItemBoughtEvent = decode_json(
+            "trade::ItemBoughtEvent",
+            &json!({
+                "kiosk": "0xa1",
+                "shared_purchase_cap": "0xa3",
+                "token_id": "0xa4",
+                "collection_type": { "name": KUMO },
+                "price": "2500000000",
+                "marketplace_fee": "50000000",
+                "royalty_fee": 125000000,
+                "seller": "0xb1",
+                "buyer": "0xb2"
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(event.royalty_fee, 125_000_000);
+        assert_eq!(event.buyer, "0xb2");
+    }
+
+    #[test]
+    fn test_decode_offers() {
+        let offer: OfferEvent = decode_json(
+            "escrow::OfferEvent",
+            &json!({
+                "offer_id": "0xc1",
+                "offer_cap": "0xc2",
+                "kiosk": "0xa1",
+                "token_id": "0xa4",
+                "collection_type": { "name": KUMO },
+                "price": "1000",
+                "marketplace_fee": "20",
+                "royalty_fee": "50",
+                "owner": "0xb2",
+                "kiosk_standard": false
+            }),
+        )
+        .unwrap();
+        assert_eq!(offer.offer_cap, "0xc2");
+
+        let accepted: OfferAcceptedEvent = decode_json(
+            "collection_escrow::OfferAcceptedEvent",
+            &json!({
+                "offer_id": "0xd1",
+                "kiosk": "0xa1",
+                "token_id": "0xa4",
+                "collection_type": { "name": KUMO },
+                "price": "1000",
+                "marketplace_fee": "20",
+                "royalty_fee": "50",
+                "remaining": "2",
+                "seller": "0xb1",
+                "buyer": "0xb2"
+            }),
+        )
+        .unwrap();
+        assert_eq!(accepted.remaining, 2);
+    }
+
+    #[test]
+    fn test_v1_payloads_decode_into_current_shape() {
+        let payload = json!({
+            "kiosk": "0xa1",
+            "kiosk_owner_cap": "0xa2",
+            "shared_purchase_cap": "0xa3",
+            "token_id": "0xa4",
+            "collection_type": { "name": KUMO },
+            "price": "2500000000",
+            "marketplace_fee": "50000000",
+            "royalty_fee": "125000000",
+            "owner": "0xb1"
+        });
+
+        let event = ItemListedEvent::decode_payload("trade::ItemListedEvent", &payload).unwrap();
+        assert!(event.kiosk_standard);
+        assert_eq!(event.price, 2_500_000_000);
+
+        // Upgraded packages emit the field, whichever package the type names
+        let mut payload = payload;
+        payload["kiosk_standard"] = json!(false);
+        let event = ItemListedEvent::decode_payload("trade::ItemListedEvent", &payload).unwrap();
+        assert!(!event.kiosk_standard);
+    }
+
+    #[test]
+    fn test_rejects_malformed_amount() {
+        let result = decode_json::<PersonalFeeSetEvent>(
+            "marketplace::PersonalFeeSetEvent",
+            &json!({ "owner": "0xb1", "fee": "two percent" }),
+        );
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,118 @@
+pub mod kiosk;
+pub mod launchpad;
+pub mod marketplace;
+
+use anyhow::{Context, Error};
+use serde::{Deserialize, Deserializer, de::DeserializeOwned};
+use serde_json::Value;
+use sui_sdk::rpc_types::SuiEvent;
+
+/// Decodes the `parsed_json` payload of a Hokko event into its typed model.
+pub fn decode<T: DeserializeOwned>(event: &SuiEvent) -> Result<T, Error> {
+    decode_json(&event.type_.to_string(), &event.parsed_json)
+}
+
+pub fn decode_json<T: DeserializeOwned>(event_type: &str, parsed_json: &Value) -> Result<T, Error> {
+    T::deserialize(parsed_json).with_context(|| format!("Decoding {event_type}"))
+}
+
+/// Marketplace event whose payload may differ between package versions.
+pub trait MarketplaceEvent: DeserializeOwned {
+    /// Decodes the payload in whichever shape it has. Events whose shape never changed
+    /// decode the same way for every version.
+    fn decode_payload(event_type: &str, parsed_json: &Value) -> Result<Self, Error> {
+        decode_json(event_type, parsed_json)
+    }
+}
+
+/// Decodes a marketplace event of any package version. The shape is told from the payload
+/// itself: neither the type's package nor the emitting package say which code built it.
+pub fn decode_marketplace<T: MarketplaceEvent>(event: &SuiEvent) -> Result<T, Error> {
+    T::decode_payload(&event.type_.to_string(), &event.parsed_json)
+}
+
+/// Decodes `T`, or its first version `V1` when the payload predates `kiosk_standard`.
+fn decode_with_v1<T, V1>(event_type: &str, parsed_json: &Value) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+    V1: DeserializeOwned + Into<T>,
+{
+    if parsed_json.get("kiosk_standard").is_some() {
+        decode_json(event_type, parsed_json)
+    } else {
+        decode_json::<V1>(event_type, parsed_json).map(Into::into)
+    }
+}
+
+/// Move `u64` values are rendered as JSON strings, but numbers are accepted as well.
+fn move_u64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum U64 {
+        Number(u64),
+        String(String),
+    }
+
+    match U64::deserialize(deserializer)? {
+        U64::Number(value) => Ok(value),
+        U64::String(value) => value.parse().map_err(serde::de::Error::custom),
+    }
+}
+
+/// `std::type_name::TypeName` arrives as `{ "name": "<address>::<module>::<Name>" }` without
+/// the `0x` prefix. Normalises it to the `0x<package>::<module>::<Name>` form used in the
+/// database.
+fn type_name<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum TypeName {
+        Struct { name: String },
+        String(String),
+    }
+
+    let name = match TypeName::deserialize(deserializer)? {
+        TypeName::Struct { name } | TypeName::String(name) => name,
+    };
+    if name.starts_with("0x") {
+        Ok(name)
+    } else {
+        Ok(format!("0x{name}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::marketplace::ItemListedEvent;
+    use super::*;
+
+    #[test]
+    fn test_type_name_accepts_struct_and_string() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            #[serde(deserialize_with = "type_name")]
+            collection_type: String,
+        }
+
+        let decoded: Wrapper =
+            serde_json::from_value(json!({ "collection_type": { "name": "2::kiosk::Item" } }))
+                .unwrap();
+        assert_eq!(decoded.collection_type, "0x2::kiosk::Item");
+
+        let decoded: Wrapper =
+            serde_json::from_value(json!({ "collection_type": "0x2::kiosk::Item" })).unwrap();
+        assert_eq!(decoded.collection_type, "0x2::kiosk::Item");
+    }
+
+    #[test]
+    fn test_decoding_error_names_the_event_type() {
+        let error = decode_json::<ItemListedEvent>(
+            "0x66a4::trade::ItemListedEvent",
+            &json!({ "kiosk": "0x1" }),
+        )
+        .unwrap_err();
+
+        assert!(format!("{error:#}").contains("0x66a4::trade::ItemListedEvent"));
+    }
+}
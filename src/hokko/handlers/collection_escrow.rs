@@ -0,0 +1,149 @@
+//! `collection_escrow` module: bids on any NFT of a collection that can be filled several
+//! times. Receipts share their payload with `escrow` and use its handlers.
+
+use anyhow::Error;
+use sea_orm::TransactionTrait;
+
+use crate::AppState;
+use crate::entity::collection_offer;
+use crate::hokko::dispatcher::EventContext;
+use crate::hokko::events::decode_marketplace;
+use crate::hokko::events::marketplace::{NewOfferEvent, OfferAcceptedEvent, OfferRevokedEvent};
+use crate::hokko::handlers::{Sale, apply_sale, ensure_nft};
+use crate::store::collection_offer::{insert_collection_offer, remove_collection_offer};
+use crate::store::event::{
+    LISTING_EVENTS, NewNftEvent, NftEventKind, event_time, has_newer_event, insert_nft_event,
+    is_collection_offer_closed,
+};
+use crate::store::listing::remove_token_listings;
+
+pub async fn on_new_offer(app_state: AppState, context: EventContext) -> Result<(), Error> {
+    let event: NewOfferEvent = decode_marketplace(&context.event)?;
+
+    let db = app_state.db.as_ref();
+
+    // A replayed offer whose closing was already indexed must not be recreated
+    if is_collection_offer_closed(db, &event.offer_id).await? {
+        return Ok(());
+    }
+
+    insert_collection_offer(
+        db,
+        collection_offer::Model {
+            collection_type: event.collection_type,
+            kiosk: event.kiosk,
+            offer_id: event.offer_id,
+            offer_cap: event.offer_cap,
+            policy_id: event.policy_id,
+            price: event.price as i64,
+            marketplace_fee: event.marketplace_fee as i64,
+            royalty_fee: event.royalty_fee as i64,
+            owner_wallet_address: event.owner,
+            kiosk_standard: event.kiosk_standard,
+            created_at: event_time(context.timestamp_ms),
+        },
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// One fill of a collection offer. The sale is recorded against the NFT that was sold, with
+/// the offer ID kept in the event description so every fill links back to its offer. All
+/// writes of the fill share one transaction.
+pub async fn on_offer_accepted(app_state: AppState, context: EventContext) -> Result<(), Error> {
+    let event: OfferAcceptedEvent = decode_marketplace(&context.event)?;
+    let db = app_state.db.as_ref();
+
+    if !ensure_nft(&app_state, &event.token_id).await? {
+        eprintln!(
+            "Sale of {} in {} only counts towards the collection: the NFT has no display data",
+            event.token_id, context.digest
+        );
+    }
+
+    let txn = db.begin().await?;
+    let recorded = insert_nft_event(
+        &txn,
+        NewNftEvent {
+            kind: NftEventKind::AcceptCollectionOffer,
+            token_id: event.token_id.clone(),
+            collection_type: event.collection_type.clone(),
+            digest: context.digest.clone(),
+            price: Some(event.price),
+            marketplace_fee: Some(event.marketplace_fee),
+            royalty_fee: Some(event.royalty_fee),
+            sender: event.seller.clone(),
+            receiver: Some(event.buyer.clone()),
+            description: Some(context.event.parsed_json.clone()),
+            timestamp_ms: context.timestamp_ms,
+        },
+    )
+    .await?;
+
+    if event.remaining == 0 {
+        remove_collection_offer(
+            &txn,
+            &event.collection_type,
+            &event.offer_id,
+            event_time(context.timestamp_ms),
+        )
+        .await?;
+    }
+    if !has_newer_event(&txn, &event.token_id, LISTING_EVENTS, context.timestamp_ms).await? {
+        remove_token_listings(&txn, &event.token_id, event_time(context.timestamp_ms)).await?;
+    }
+    apply_sale(
+        &txn,
+        Sale {
+            token_id: &event.token_id,
+            collection_type: &event.collection_type,
+            buyer: &event.buyer,
+            kiosk: Some(&event.kiosk),
+            price: event.price,
+            timestamp_ms: context.timestamp_ms,
+        },
+        recorded,
+    )
+    .await?;
+    txn.commit().await?;
+
+    Ok(())
+}
+
+/// The revocation is recorded under the offer ID so a replayed `NewOfferEvent` can tell the
+/// offer is gone.
+pub async fn on_offer_revoked(app_state: AppState, context: EventContext) -> Result<(), Error> {
+    let event: OfferRevokedEvent = decode_marketplace(&context.event)?;
+
+    let db = app_state.db.as_ref();
+
+    let txn = db.begin().await?;
+    insert_nft_event(
+        &txn,
+        NewNftEvent {
+            kind: NftEventKind::RevokeCollectionOffer,
+            token_id: event.offer_id.clone(),
+            collection_type: event.collection_type.clone(),
+            digest: context.digest.clone(),
+            price: None,
+            marketplace_fee: None,
+            royalty_fee: None,
+            sender: event.owner,
+            receiver: None,
+            description: Some(context.event.parsed_json.clone()),
+            timestamp_ms: context.timestamp_ms,
+        },
+    )
+    .await?;
+    remove_collection_offer(
+        &txn,
+        &event.collection_type,
+        &event.offer_id,
+        event_time(context.timestamp_ms),
+    )
+    .await?;
+    txn.commit().await?;
+
+    Ok(())
+}
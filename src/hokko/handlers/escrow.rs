@@ -0,0 +1,244 @@
+//! `escrow` module: offers on a single NFT, funded upfront by the buyer.
+
+use anyhow::Error;
+use sea_orm::TransactionTrait;
+
+use crate::AppState;
+use crate::entity::offer;
+use crate::hokko::dispatcher::EventContext;
+use crate::hokko::events::decode_marketplace;
+use crate::hokko::events::marketplace::{
+    AcceptOfferEvent, DeclineOfferEvent, OfferEvent, ReceiptCreatedEvent, ReceiptDestroyedEvent,
+    RevokeOfferEvent,
+};
+use crate::hokko::handlers::{Sale, apply_sale, ensure_nft};
+use crate::store::event::{
+    LISTING_EVENTS, NewNftEvent, NftEventKind, OFFER_CLOSING_EVENTS, event_time, has_newer_event,
+    insert_nft_event, is_offer_closed,
+};
+use crate::store::listing::remove_token_listings;
+use crate::store::offer::{insert_offer, remove_offer};
+
+/// Records the offer in the activity feed and stores it, in one transaction. The NFT is
+/// resolved first so the transaction does not wait on the node.
+pub async fn on_offer(app_state: AppState, context: EventContext) -> Result<(), Error> {
+    let event: OfferEvent = decode_marketplace(&context.event)?;
+    let db = app_state.db.as_ref();
+
+    // A replayed offer may already have been closed by a later checkpoint
+    let open = !is_offer_closed(db, &event.token_id, &event.offer_id, OFFER_CLOSING_EVENTS).await?;
+    let has_nft = open && ensure_nft(&app_state, &event.token_id).await?;
+    if open && !has_nft {
+        eprintln!(
+            "Skipping offer {} in {}: NFT {} has no display data",
+            event.offer_id, context.digest, event.token_id
+        );
+    }
+
+    let txn = db.begin().await?;
+    insert_nft_event(
+        &txn,
+        NewNftEvent {
+            kind: NftEventKind::Offer,
+            token_id: event.token_id.clone(),
+            collection_type: event.collection_type.clone(),
+            digest: context.digest.clone(),
+            price: Some(event.price),
+            marketplace_fee: Some(event.marketplace_fee),
+            royalty_fee: Some(event.royalty_fee),
+            sender: context.sender.clone(),
+            receiver: None,
+            description: Some(context.event.parsed_json.clone()),
+            timestamp_ms: context.timestamp_ms,
+        },
+    )
+    .await?;
+
+    if has_nft {
+        let offered_at = event_time(context.timestamp_ms);
+        insert_offer(
+            &txn,
+            offer::Model {
+                collection_type: event.collection_type,
+                kiosk: event.kiosk,
+                offer_id: event.offer_id,
+                offer_cap: event.offer_cap,
+                token_id: event.token_id.clone(),
+                price: event.price as i64,
+                marketplace_fee: event.marketplace_fee as i64,
+                royalty_fee: event.royalty_fee as i64,
+                owner_wallet_address: event.owner,
+                kiosk_standard: event.kiosk_standard,
+                created_at: offered_at,
+                updated_at: offered_at,
+                nft_id: Some(event.token_id),
+            },
+        )
+        .await?;
+    }
+    txn.commit().await?;
+
+    Ok(())
+}
+
+/// Records the sale and its volume, closes the offer and hands the NFT to the buyer, in
+/// one transaction.
+pub async fn on_offer_accepted(app_state: AppState, context: EventContext) -> Result<(), Error> {
+    let event: AcceptOfferEvent = decode_marketplace(&context.event)?;
+    let db = app_state.db.as_ref();
+
+    if !ensure_nft(&app_state, &event.token_id).await? {
+        eprintln!(
+            "Sale of {} in {} only counts towards the collection: the NFT has no display data",
+            event.token_id, context.digest
+        );
+    }
+
+    let txn = db.begin().await?;
+    let recorded = insert_nft_event(
+        &txn,
+        NewNftEvent {
+            kind: NftEventKind::AcceptOffer,
+            token_id: event.token_id.clone(),
+            collection_type: event.collection_type.clone(),
+            digest: context.digest.clone(),
+            price: Some(event.price),
+            marketplace_fee: Some(event.marketplace_fee),
+            royalty_fee: Some(event.royalty_fee),
+            sender: event.seller.clone(),
+            receiver: Some(event.buyer.clone()),
+            description: Some(context.event.parsed_json.clone()),
+            timestamp_ms: context.timestamp_ms,
+        },
+    )
+    .await?;
+    remove_offer(&txn, &event.offer_id, &event.token_id).await?;
+
+    if !has_newer_event(&txn, &event.token_id, LISTING_EVENTS, context.timestamp_ms).await? {
+        remove_token_listings(&txn, &event.token_id, event_time(context.timestamp_ms)).await?;
+    }
+    apply_sale(
+        &txn,
+        Sale {
+            token_id: &event.token_id,
+            collection_type: &event.collection_type,
+            buyer: &event.buyer,
+            kiosk: Some(&event.kiosk),
+            price: event.price,
+            timestamp_ms: context.timestamp_ms,
+        },
+        recorded,
+    )
+    .await?;
+    txn.commit().await?;
+
+    Ok(())
+}
+
+/// Records the decline and closes the offer, in one transaction.
+pub async fn on_offer_declined(app_state: AppState, context: EventContext) -> Result<(), Error> {
+    let event: DeclineOfferEvent = decode_marketplace(&context.event)?;
+    let db = app_state.db.as_ref();
+
+    let txn = db.begin().await?;
+    insert_nft_event(
+        &txn,
+        NewNftEvent {
+            kind: NftEventKind::DeclineOffer,
+            token_id: event.token_id.clone(),
+            collection_type: event.collection_type,
+            digest: context.digest.clone(),
+            price: None,
+            marketplace_fee: None,
+            royalty_fee: None,
+            sender: context.sender.clone(),
+            receiver: None,
+            description: Some(context.event.parsed_json.clone()),
+            timestamp_ms: context.timestamp_ms,
+        },
+    )
+    .await?;
+    remove_offer(&txn, &event.offer_id, &event.token_id).await?;
+    txn.commit().await?;
+
+    Ok(())
+}
+
+/// Records the revocation and closes the offer, in one transaction.
+pub async fn on_offer_revoked(app_state: AppState, context: EventContext) -> Result<(), Error> {
+    let event: RevokeOfferEvent = decode_marketplace(&context.event)?;
+    let db = app_state.db.as_ref();
+
+    let txn = db.begin().await?;
+    insert_nft_event(
+        &txn,
+        NewNftEvent {
+            kind: NftEventKind::RevokeOffer,
+            token_id: event.token_id.clone(),
+            collection_type: event.collection_type,
+            digest: context.digest.clone(),
+            price: None,
+            marketplace_fee: None,
+            royalty_fee: None,
+            sender: context.sender.clone(),
+            receiver: None,
+            description: Some(context.event.parsed_json.clone()),
+            timestamp_ms: context.timestamp_ms,
+        },
+    )
+    .await?;
+    remove_offer(&txn, &event.offer_id, &event.token_id).await?;
+    txn.commit().await?;
+
+    Ok(())
+}
+
+/// Receipts only show up in the activity feed: the sale itself is handled by
+/// `on_offer_accepted`.
+pub async fn on_receipt_created(app_state: AppState, context: EventContext) -> Result<(), Error> {
+    let event: ReceiptCreatedEvent = decode_marketplace(&context.event)?;
+
+    insert_nft_event(
+        app_state.db.as_ref(),
+        NewNftEvent {
+            kind: NftEventKind::ReceiptCreated,
+            token_id: event.token_id,
+            collection_type: event.collection_type,
+            digest: context.digest.clone(),
+            price: None,
+            marketplace_fee: None,
+            royalty_fee: None,
+            sender: event.owner,
+            receiver: None,
+            description: Some(context.event.parsed_json.clone()),
+            timestamp_ms: context.timestamp_ms,
+        },
+    )
+    .await?;
+
+    Ok(())
+}
+
+pub async fn on_receipt_destroyed(app_state: AppState, context: EventContext) -> Result<(), Error> {
+    let event: ReceiptDestroyedEvent = decode_marketplace(&context.event)?;
+
+    insert_nft_event(
+        app_state.db.as_ref(),
+        NewNftEvent {
+            kind: NftEventKind::ReceiptDestroyed,
+            token_id: event.token_id,
+            collection_type: event.collection_type,
+            digest: context.digest.clone(),
+            price: None,
+            marketplace_fee: None,
+            royalty_fee: None,
+            sender: event.owner,
+            receiver: None,
+            description: Some(context.event.parsed_json.clone()),
+            timestamp_ms: context.timestamp_ms,
+        },
+    )
+    .await?;
+
+    Ok(())
+}
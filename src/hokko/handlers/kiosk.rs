@@ -0,0 +1,250 @@
+//! `0x2::kiosk` module of the Sui framework: listings and sales of tracked collections made
+//! directly through a kiosk rather than through Hokko.
+//!
+//! Native listings have no purchase cap. The kiosk ID takes its place in the listing key,
+//! which stays unique because an item can only be listed once per kiosk.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use anyhow::Error;
+use sea_orm::{EntityTrait, TransactionTrait};
+
+use crate::AppState;
+use crate::entity::{listing, nft};
+use crate::hokko::dispatcher::EventContext;
+use crate::hokko::events::decode;
+use crate::hokko::events::kiosk::{ItemDelisted, ItemListed, ItemPurchased};
+use crate::hokko::handlers::{Sale, apply_sale, ensure_nft};
+use crate::store::event::{
+    LISTING_EVENTS, NewNftEvent, NftEventKind, event_time, has_newer_event, insert_nft_event,
+};
+use crate::store::kiosk::find_kiosk_owner;
+use crate::store::listing::{find_listing, remove_listing, replace_listing};
+
+pub async fn on_item_listed(
+    app_state: AppState,
+    context: EventContext,
+    tracked: Arc<HashSet<String>>,
+) -> Result<(), Error> {
+    let Some(collection_type) = tracked_item(&context, &tracked) else {
+        return Ok(());
+    };
+    let event: ItemListed = decode(&context.event)?;
+    let db = app_state.db.as_ref();
+    let has_nft = ensure_nft(&app_state, &event.id).await?;
+
+    let txn = db.begin().await?;
+    insert_nft_event(
+        &txn,
+        NewNftEvent {
+            kind: NftEventKind::List,
+            token_id: event.id.clone(),
+            collection_type: collection_type.clone(),
+            digest: context.digest.clone(),
+            price: Some(event.price),
+            marketplace_fee: None,
+            royalty_fee: None,
+            sender: context.sender.clone(),
+            receiver: None,
+            description: Some(context.event.parsed_json.clone()),
+            timestamp_ms: context.timestamp_ms,
+        },
+    )
+    .await?;
+
+    if has_newer_event(&txn, &event.id, LISTING_EVENTS, context.timestamp_ms).await? {
+        txn.commit().await?;
+        return Ok(());
+    }
+    if !has_nft {
+        eprintln!(
+            "Skipping kiosk listing of {} in {}: the NFT has no display data",
+            event.id, context.digest
+        );
+        txn.commit().await?;
+        return Ok(());
+    }
+
+    let listed_at = event_time(context.timestamp_ms);
+    replace_listing(
+        &txn,
+        listing::Model {
+            collection_type,
+            kiosk: event.kiosk.clone(),
+            kiosk_owner_cap: String::new(),
+            shared_purchase_cap: event.kiosk,
+            token_id: event.id,
+            price: event.price as i64,
+            marketplace_fee: 0,
+            royalty_fee: 0,
+            owner_wallet_address: context.sender.clone(),
+            kiosk_standard: true,
+            created_at: listed_at,
+            updated_at: listed_at,
+        },
+    )
+    .await?;
+    txn.commit().await?;
+
+    Ok(())
+}
+
+pub async fn on_item_delisted(
+    app_state: AppState,
+    context: EventContext,
+    tracked: Arc<HashSet<String>>,
+) -> Result<(), Error> {
+    let Some(collection_type) = tracked_item(&context, &tracked) else {
+        return Ok(());
+    };
+    let event: ItemDelisted = decode(&context.event)?;
+    let db = app_state.db.as_ref();
+
+    let txn = db.begin().await?;
+    insert_nft_event(
+        &txn,
+        NewNftEvent {
+            kind: NftEventKind::Delist,
+            token_id: event.id.clone(),
+            collection_type,
+            digest: context.digest.clone(),
+            price: None,
+            marketplace_fee: None,
+            royalty_fee: None,
+            sender: context.sender.clone(),
+            receiver: None,
+            description: Some(context.event.parsed_json.clone()),
+            timestamp_ms: context.timestamp_ms,
+        },
+    )
+    .await?;
+
+    if !has_newer_event(&txn, &event.id, LISTING_EVENTS, context.timestamp_ms).await? {
+        remove_listing(
+            &txn,
+            &event.kiosk,
+            &event.id,
+            event_time(context.timestamp_ms),
+        )
+        .await?;
+    }
+    txn.commit().await?;
+
+    Ok(())
+}
+
+/// Records the purchase like a Hokko sale. The buyer is the sender; the seller is whoever
+/// listed the NFT, else the owner of the kiosk named in the event, else the NFT's last
+/// known holder unless that is already the buyer.
+pub async fn on_item_purchased(
+    app_state: AppState,
+    context: EventContext,
+    tracked: Arc<HashSet<String>>,
+) -> Result<(), Error> {
+    let Some(collection_type) = tracked_item(&context, &tracked) else {
+        return Ok(());
+    };
+    let event: ItemPurchased = decode(&context.event)?;
+    let db = app_state.db.as_ref();
+
+    // Resolved first: fetching an unknown NFT returns its post-purchase state, which already
+    // names the buyer as holder
+    let Some(seller) = find_seller(&app_state, &context, &event).await? else {
+        eprintln!(
+            "Skipping kiosk sale of {} in {}: the seller of kiosk {} is unknown",
+            event.id, context.digest, event.kiosk
+        );
+        return Ok(());
+    };
+    if !ensure_nft(&app_state, &event.id).await? {
+        eprintln!(
+            "Kiosk sale of {} in {} only counts towards the collection: the NFT has no display data",
+            event.id, context.digest
+        );
+    }
+
+    let txn = db.begin().await?;
+    let recorded = insert_nft_event(
+        &txn,
+        NewNftEvent {
+            kind: NftEventKind::Buy,
+            token_id: event.id.clone(),
+            collection_type: collection_type.clone(),
+            digest: context.digest.clone(),
+            price: Some(event.price),
+            marketplace_fee: None,
+            royalty_fee: None,
+            sender: seller,
+            receiver: Some(context.sender.clone()),
+            description: Some(context.event.parsed_json.clone()),
+            timestamp_ms: context.timestamp_ms,
+        },
+    )
+    .await?;
+
+    if !has_newer_event(&txn, &event.id, LISTING_EVENTS, context.timestamp_ms).await? {
+        remove_listing(
+            &txn,
+            &event.kiosk,
+            &event.id,
+            event_time(context.timestamp_ms),
+        )
+        .await?;
+    }
+    // The purchased item leaves the kiosk; where the buyer puts it is not part of the event
+    apply_sale(
+        &txn,
+        Sale {
+            token_id: &event.id,
+            collection_type: &collection_type,
+            buyer: &context.sender,
+            kiosk: None,
+            price: event.price,
+            timestamp_ms: context.timestamp_ms,
+        },
+        recorded,
+    )
+    .await?;
+    txn.commit().await?;
+
+    Ok(())
+}
+
+/// Collection type of the kiosk item if it belongs to a tracked collection. Events of Hokko
+/// transactions are skipped since the Hokko events already record them.
+fn tracked_item(context: &EventContext, tracked: &HashSet<String>) -> Option<String> {
+    if context.hokko_transaction {
+        return None;
+    }
+
+    let item_type = context
+        .event
+        .type_
+        .type_params
+        .first()?
+        .to_canonical_string(true);
+    tracked.contains(&item_type).then_some(item_type)
+}
+
+async fn find_seller(
+    app_state: &AppState,
+    context: &EventContext,
+    event: &ItemPurchased,
+) -> Result<Option<String>, Error> {
+    let db = app_state.db.as_ref();
+
+    if let Some(listing) = find_listing(db, &event.kiosk, &event.id).await? {
+        return Ok(Some(listing.owner_wallet_address));
+    }
+    if let Some(owner) = find_kiosk_owner(db, &event.kiosk).await? {
+        return Ok(Some(owner));
+    }
+
+    let holder = nft::Entity::find_by_id(event.id.clone())
+        .one(db)
+        .await?
+        .map(|nft| nft.holder)
+        .filter(|holder| !holder.is_empty() && *holder != context.sender);
+    Ok(holder)
+}
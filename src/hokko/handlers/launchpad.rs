@@ -0,0 +1,349 @@
+//! `launchpad` (admin) and `launch_manager` (creator) modules: launchpad collections and
+//! their lifecycle.
+
+use anyhow::Error;
+use sea_orm::TransactionTrait;
+
+use crate::AppState;
+use crate::entity::launchpad_collection;
+use crate::hokko::dispatcher::EventContext;
+use crate::hokko::events::decode;
+use crate::hokko::events::launchpad::{
+    ItemMintedEvent, LaunchCreatorEvent, LaunchInitializedEvent, LaunchTimestampsUpdatedEvent,
+    LaunchWhitelistUpdatedEvent, LaunchpadAdminEvent,
+};
+use crate::hokko::handlers::ensure_nft;
+use crate::hokko::launch_state::{LaunchState, LaunchTransition, MintPhase};
+use crate::processor::model::{NftData, NftDataParams, OwnerType};
+use crate::store::event::{NewNftEvent, NftEventKind, event_time, insert_nft_event};
+use crate::store::launchpad::{
+    LaunchTimes, decrement_whitelist_allocation, find_launch, increment_mint_count, insert_launch,
+    mark_launch_event, remove_whitelist_addresses, set_launch_state, set_launch_times,
+    touch_launch, upsert_whitelist_address,
+};
+use crate::store::store::store_nft;
+
+pub async fn on_launch_initialized(
+    app_state: AppState,
+    context: EventContext,
+) -> Result<(), Error> {
+    let event: LaunchInitializedEvent = decode(&context.event)?;
+    let registered_at = event_time(context.timestamp_ms);
+
+    insert_launch(
+        app_state.db.as_ref(),
+        launchpad_collection::Model {
+            collection_type: event.collection_type,
+            kiosk_standard: event.kiosk_standard,
+            name: event.name,
+            description: event.description,
+            supply: event.supply.to_string(),
+            price: event.price as i64,
+            max_item_per_address: event.max_item_per_address.to_string(),
+            white_list_enabled: event.white_list_enabled,
+            white_list_price: event.white_list_price as i64,
+            white_list_supply: event.white_list_supply.to_string(),
+            custom_enabled: event.custom_enabled,
+            custom_name: event.custom_name,
+            custom_price: event.custom_price as i64,
+            custom_supply: event.custom_supply.to_string(),
+            mint_count: 0,
+            created_at: registered_at,
+            updated_at: registered_at,
+            creator_cap: event.creator_cap,
+            launch_id: event.launch_id,
+            native: event.native,
+            state: LaunchState::Pending.as_str().to_string(),
+            cover_image: event.cover_image,
+            start_time: event_time(event.start_time),
+            white_list_start_time: event_time(event.white_list_start_time),
+            custom_start_time: event_time(event.custom_start_time),
+            owner_wallet_address: event.creator,
+        },
+    )
+    .await?;
+
+    Ok(())
+}
+
+pub async fn on_approved(app_state: AppState, context: EventContext) -> Result<(), Error> {
+    let event: LaunchpadAdminEvent = decode(&context.event)?;
+    transition(
+        &app_state,
+        &context,
+        &event.launch_id,
+        LaunchTransition::Approve,
+    )
+    .await
+}
+
+pub async fn on_rejected(app_state: AppState, context: EventContext) -> Result<(), Error> {
+    let event: LaunchpadAdminEvent = decode(&context.event)?;
+    transition(
+        &app_state,
+        &context,
+        &event.launch_id,
+        LaunchTransition::Reject,
+    )
+    .await
+}
+
+pub async fn on_paused(app_state: AppState, context: EventContext) -> Result<(), Error> {
+    let event: LaunchpadAdminEvent = decode(&context.event)?;
+    transition(
+        &app_state,
+        &context,
+        &event.launch_id,
+        LaunchTransition::Pause,
+    )
+    .await
+}
+
+pub async fn on_resumed(app_state: AppState, context: EventContext) -> Result<(), Error> {
+    let event: LaunchpadAdminEvent = decode(&context.event)?;
+    transition(
+        &app_state,
+        &context,
+        &event.launch_id,
+        LaunchTransition::Resume,
+    )
+    .await
+}
+
+pub async fn on_creator_paused(app_state: AppState, context: EventContext) -> Result<(), Error> {
+    let event: LaunchCreatorEvent = decode(&context.event)?;
+    transition(
+        &app_state,
+        &context,
+        &event.launch_id,
+        LaunchTransition::CreatorPause,
+    )
+    .await
+}
+
+pub async fn on_creator_resumed(app_state: AppState, context: EventContext) -> Result<(), Error> {
+    let event: LaunchCreatorEvent = decode(&context.event)?;
+    transition(
+        &app_state,
+        &context,
+        &event.launch_id,
+        LaunchTransition::CreatorResume,
+    )
+    .await
+}
+
+pub async fn on_timestamps_updated(
+    app_state: AppState,
+    context: EventContext,
+) -> Result<(), Error> {
+    let event: LaunchTimestampsUpdatedEvent = decode(&context.event)?;
+    let db = app_state.db.as_ref();
+    let happened_at = event_time(context.timestamp_ms);
+
+    let Some(launch) = find_launch(db, &event.launch_id).await? else {
+        eprintln!(
+            "Ignoring timestamps of unknown launch {} in {}",
+            event.launch_id, context.digest
+        );
+        return Ok(());
+    };
+    if launch.updated_at > happened_at {
+        return Ok(());
+    }
+
+    set_launch_times(
+        db,
+        &event.launch_id,
+        LaunchTimes {
+            start_time: event_time(event.start_time),
+            white_list_start_time: event_time(event.white_list_start_time),
+            custom_start_time: event_time(event.custom_start_time),
+        },
+        happened_at,
+    )
+    .await?;
+
+    Ok(())
+}
+
+pub async fn on_whitelist_updated(app_state: AppState, context: EventContext) -> Result<(), Error> {
+    let event: LaunchWhitelistUpdatedEvent = decode(&context.event)?;
+    let db = app_state.db.as_ref();
+    let happened_at = event_time(context.timestamp_ms);
+
+    let Some(launch) = find_launch(db, &event.launch_id).await? else {
+        eprintln!(
+            "Ignoring whitelist of unknown launch {} in {}",
+            event.launch_id, context.digest
+        );
+        return Ok(());
+    };
+    if launch.updated_at > happened_at {
+        // Replaying would restore allocations that later mints already used up
+        return Ok(());
+    }
+
+    // Mints of the same checkpoint share its timestamp, so only the marker tells whether
+    // this update was applied before
+    let txn = db.begin().await?;
+    let first_time = mark_launch_event(
+        &txn,
+        &event.launch_id,
+        &context.digest,
+        context.event.id.event_seq,
+        "whitelist-update",
+        happened_at,
+    )
+    .await?;
+    if !first_time {
+        return Ok(());
+    }
+
+    remove_whitelist_addresses(&txn, &event.launch_id, &event.removed).await?;
+    for entry in &event.added {
+        let allocation = i32::try_from(entry.allocation).unwrap_or(i32::MAX);
+        upsert_whitelist_address(&txn, &event.launch_id, &entry.address, allocation).await?;
+    }
+    touch_launch(&txn, &event.launch_id, happened_at).await?;
+    txn.commit().await?;
+
+    Ok(())
+}
+
+/// Stores the minted NFT and accounts the mint to the launch and, for whitelist mints, to
+/// the minter's allocation. The `mint` activity row doubles as the marker that the counters
+/// were already updated, so a re-indexed checkpoint does not count the mint twice.
+pub async fn on_item_minted(app_state: AppState, context: EventContext) -> Result<(), Error> {
+    let event: ItemMintedEvent = decode(&context.event)?;
+    let db = app_state.db.as_ref();
+
+    let Some(launch) = find_launch(db, &event.launch_id).await? else {
+        eprintln!(
+            "Ignoring mint of {} from unknown launch {} in {}",
+            event.nft_id, event.launch_id, context.digest
+        );
+        return Ok(());
+    };
+
+    let minted_at = event_time(context.timestamp_ms);
+    let phase = MintPhase::active(&launch, minted_at).unwrap_or_else(|| {
+        eprintln!(
+            "Mint of {} in {} happened before any phase of launch {} started",
+            event.nft_id, context.digest, event.launch_id
+        );
+        MintPhase::Public
+    });
+
+    // The NFT row has to exist before anything else refers to it
+    if !ensure_nft(&app_state, &event.nft_id).await? {
+        let mut nft = NftData::new(NftDataParams {
+            token_id: event.nft_id.clone(),
+            collection_type: event.collection_type.clone(),
+            digest: context.digest.clone(),
+        });
+        nft.item_holder = Some(OwnerType {
+            address_owner: Some(event.minter.clone()),
+            object_owner: None,
+        });
+        store_nft(app_state.clone(), nft).await?;
+    }
+
+    let mut description = context.event.parsed_json.clone();
+    if let Some(fields) = description.as_object_mut() {
+        fields.insert("phase".to_string(), phase.as_str().into());
+    }
+
+    let txn = db.begin().await?;
+    let recorded = insert_nft_event(
+        &txn,
+        NewNftEvent {
+            kind: NftEventKind::Mint,
+            token_id: event.nft_id.clone(),
+            collection_type: event.collection_type.clone(),
+            digest: context.digest.clone(),
+            price: Some(event.price),
+            marketplace_fee: None,
+            royalty_fee: None,
+            sender: event.minter.clone(),
+            receiver: None,
+            description: Some(description),
+            timestamp_ms: context.timestamp_ms,
+        },
+    )
+    .await?;
+    if !recorded {
+        return Ok(());
+    }
+
+    increment_mint_count(&txn, &event.launch_id, minted_at).await?;
+    if phase == MintPhase::Whitelist
+        && !decrement_whitelist_allocation(&txn, &event.launch_id, &event.minter).await?
+    {
+        eprintln!(
+            "Whitelist mint of {} in {} by {} exceeds its allocation",
+            event.nft_id, context.digest, event.minter
+        );
+    }
+    txn.commit().await?;
+
+    Ok(())
+}
+
+/// Moves the launch to its next state. Transitions the state machine refuses are reported
+/// and skipped rather than failing the checkpoint: the chain already accepted them, so
+/// retrying could never succeed.
+async fn transition(
+    app_state: &AppState,
+    context: &EventContext,
+    launch_id: &str,
+    transition: LaunchTransition,
+) -> Result<(), Error> {
+    let db = app_state.db.as_ref();
+    let Some(launch) = find_launch(db, launch_id).await? else {
+        eprintln!(
+            "Ignoring {transition} of unknown launch {launch_id} in {}",
+            context.digest
+        );
+        return Ok(());
+    };
+
+    let happened_at = event_time(context.timestamp_ms);
+    if launch.updated_at > happened_at {
+        // Replayed event; a newer state is already stored
+        return Ok(());
+    }
+
+    let current = match launch.state.parse::<LaunchState>() {
+        Ok(current) => current,
+        Err(e) => {
+            eprintln!("Launch {launch_id} in {}: {e}", context.digest);
+            return Ok(());
+        }
+    };
+
+    let txn = db.begin().await?;
+    let first_time = mark_launch_event(
+        &txn,
+        launch_id,
+        &context.digest,
+        context.event.id.event_seq,
+        &transition.to_string(),
+        happened_at,
+    )
+    .await?;
+    if !first_time {
+        // This transition was already applied when the checkpoint was indexed before
+        return Ok(());
+    }
+
+    match current.apply(transition) {
+        Ok(next) => set_launch_state(&txn, launch_id, next.as_str(), happened_at).await?,
+        Err(e) => eprintln!(
+            "Rejected launchpad transition for {launch_id} in {}: {e}",
+            context.digest
+        ),
+    }
+    txn.commit().await?;
+
+    Ok(())
+}
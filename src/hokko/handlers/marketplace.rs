@@ -0,0 +1,42 @@
+//! `marketplace` module: kiosks created through Hokko and per-wallet fees.
+
+use anyhow::{Context, Error};
+
+use crate::AppState;
+use crate::entity::kiosk;
+use crate::hokko::dispatcher::EventContext;
+use crate::hokko::events::decode_marketplace;
+use crate::hokko::events::marketplace::{KioskCreatedEvent, PersonalFeeSetEvent};
+use crate::store::kiosk::insert_kiosk;
+use crate::store::store::set_personal_fee;
+
+pub async fn on_kiosk_created(app_state: AppState, context: EventContext) -> Result<(), Error> {
+    let event: KioskCreatedEvent = decode_marketplace(&context.event)?;
+
+    insert_kiosk(
+        app_state.db.as_ref(),
+        kiosk::Model {
+            kiosk_ownercap: event.kiosk_owner_cap,
+            kiosk: event.kiosk,
+            personal: event.personal,
+            owner_wallet_address: event.owner,
+        },
+    )
+    .await?;
+
+    Ok(())
+}
+
+pub async fn on_personal_fee_set(app_state: AppState, context: EventContext) -> Result<(), Error> {
+    let event: PersonalFeeSetEvent = decode_marketplace(&context.event)?;
+    let fee = i32::try_from(event.fee).with_context(|| {
+        format!(
+            "Personal fee {} of {} is out of range",
+            event.fee, event.owner
+        )
+    })?;
+
+    set_personal_fee(app_state.db.as_ref(), &event.owner, fee).await?;
+
+    Ok(())
+}
@@ -0,0 +1,147 @@
+pub mod collection_escrow;
+pub mod escrow;
+pub mod kiosk;
+pub mod launchpad;
+pub mod marketplace;
+pub mod trade;
+
+use std::str::FromStr;
+
+use anyhow::{Context, Error};
+use sea_orm::{ConnectionTrait, DbErr, EntityTrait};
+use sui_sdk::types::base_types::ObjectID;
+
+use crate::AppState;
+use crate::entity::nft;
+use crate::processor::assemble::assemble_objects;
+use crate::store::collection_stats::sale_recorded;
+use crate::store::event::{SALE_EVENTS, event_time, has_newer_event};
+use crate::store::store::{add_sale_volume, ensure_collection, record_sale, store_nft};
+
+/// Makes sure `token_id` has an `Nft` row so rows referencing it can be inserted. NFTs that
+/// were never seen through the indexed collections are fetched and stored on demand.
+/// Returns `false` when the object is gone or has no display data.
+pub async fn ensure_nft(app_state: &AppState, token_id: &str) -> Result<bool, Error> {
+    let known = nft::Entity::find_by_id(token_id.to_string())
+        .one(app_state.db.as_ref())
+        .await?;
+    if known.is_some() {
+        return Ok(true);
+    }
+
+    let object_id =
+        ObjectID::from_str(token_id).with_context(|| format!("Parsing token id {token_id}"))?;
+    let objects = app_state.source.multi_get_objects(vec![object_id]).await?;
+    let nfts = assemble_objects(app_state, objects).await?;
+    if nfts.is_empty() {
+        return Ok(false);
+    }
+    for nft in nfts {
+        store_nft(app_state.clone(), nft).await?;
+    }
+
+    Ok(true)
+}
+
+/// A purchase or accepted offer, whichever module it came from.
+pub struct Sale<'a> {
+    pub token_id: &'a str,
+    pub collection_type: &'a str,
+    pub buyer: &'a str,
+    /// Kiosk the NFT was moved to, if the event names it. `None` clears the seller's kiosk.
+    pub kiosk: Option<&'a str>,
+    pub price: u64,
+    pub timestamp_ms: u64,
+}
+
+/// Applies a sale whose activity row was just written in the same transaction. Volume is
+/// only counted when `recorded` says the row is new, so re-processing a digest does not
+/// count it twice. Ownership is left alone when a later sale is already indexed. The
+/// collection row is created if needed, so the volume counts even when the NFT is unknown.
+pub async fn apply_sale<C: ConnectionTrait>(
+    db: &C,
+    sale: Sale<'_>,
+    recorded: bool,
+) -> Result<(), DbErr> {
+    if recorded {
+        ensure_collection(db, sale.collection_type).await?;
+        add_sale_volume(db, sale.token_id, sale.collection_type, sale.price).await?;
+        sale_recorded(
+            db,
+            sale.collection_type,
+            sale.price as i64,
+            event_time(sale.timestamp_ms),
+        )
+        .await?;
+    }
+    if !has_newer_event(db, sale.token_id, SALE_EVENTS, sale.timestamp_ms).await? {
+        record_sale(
+            db,
+            sale.token_id,
+            sale.buyer,
+            sale.kiosk,
+            sale.price,
+            event_time(sale.timestamp_ms),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult, Value};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_sale_of_unknown_collection_creates_it_before_counting_volume() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_exec_results(vec![
+                MockExecResult {
+                    last_insert_id: 0,
+                    rows_affected: 1,
+                };
+                16
+            ])
+            .append_query_results([vec![BTreeMap::from([(
+                "num_items".to_string(),
+                Value::BigInt(Some(0)),
+            )])]])
+            .into_connection();
+
+        apply_sale(
+            &db,
+            Sale {
+                token_id: "0xa4",
+                collection_type: "0x3::nft::Unknown",
+                buyer: "0xb2",
+                kiosk: None,
+                price: 1_000,
+                timestamp_ms: 1_760_000_000_000,
+            },
+            true,
+        )
+        .await
+        .unwrap();
+
+        let statements: Vec<String> = db
+            .into_transaction_log()
+            .iter()
+            .flat_map(|transaction| transaction.statements())
+            .map(|statement| statement.sql.clone())
+            .collect();
+        let created = statements
+            .iter()
+            .position(|sql| sql.starts_with(r#"INSERT INTO "Collection""#))
+            .expect("collection is created");
+        let counted = statements
+            .iter()
+            .position(|sql| sql.starts_with(r#"UPDATE "Collection""#))
+            .expect("collection volume is counted");
+        assert!(created < counted, "{statements:#?}");
+    }
+}
@@ -0,0 +1,229 @@
+//! `trade` module: fixed-price listings and purchases.
+
+use anyhow::Error;
+use sea_orm::TransactionTrait;
+
+use crate::AppState;
+use crate::entity::listing;
+use crate::hokko::dispatcher::EventContext;
+use crate::hokko::events::decode_marketplace;
+use crate::hokko::events::marketplace::{
+    ItemBoughtEvent, ItemDelistedEvent, ItemListedEvent, ItemUpdatedEvent,
+};
+use crate::hokko::handlers::{Sale, apply_sale, ensure_nft};
+use crate::store::event::{
+    LISTING_EVENTS, NewNftEvent, NftEventKind, event_time, has_newer_event, insert_nft_event,
+};
+use crate::store::listing::{ListingPrice, remove_listing, replace_listing, update_listing_price};
+
+/// The activity row, the listing and the collection stats it moves share one transaction.
+pub async fn on_item_listed(app_state: AppState, context: EventContext) -> Result<(), Error> {
+    let event: ItemListedEvent = decode_marketplace(&context.event)?;
+    let db = app_state.db.as_ref();
+
+    // Fetching a missing NFT goes through RPC, which must not hold the transaction open
+    let has_nft = ensure_nft(&app_state, &event.token_id).await?;
+
+    let txn = db.begin().await?;
+    insert_nft_event(
+        &txn,
+        NewNftEvent {
+            kind: NftEventKind::List,
+            token_id: event.token_id.clone(),
+            collection_type: event.collection_type.clone(),
+            digest: context.digest.clone(),
+            price: Some(event.price),
+            marketplace_fee: Some(event.marketplace_fee),
+            royalty_fee: Some(event.royalty_fee),
+            sender: context.sender.clone(),
+            receiver: None,
+            description: Some(context.event.parsed_json.clone()),
+            timestamp_ms: context.timestamp_ms,
+        },
+    )
+    .await?;
+
+    if has_newer_event(&txn, &event.token_id, LISTING_EVENTS, context.timestamp_ms).await? {
+        txn.commit().await?;
+        return Ok(());
+    }
+    if !has_nft {
+        eprintln!(
+            "Skipping listing of {} in {}: the NFT has no display data",
+            event.token_id, context.digest
+        );
+        txn.commit().await?;
+        return Ok(());
+    }
+
+    let listed_at = event_time(context.timestamp_ms);
+    replace_listing(
+        &txn,
+        listing::Model {
+            collection_type: event.collection_type,
+            kiosk: event.kiosk,
+            kiosk_owner_cap: event.kiosk_owner_cap,
+            shared_purchase_cap: event.shared_purchase_cap,
+            token_id: event.token_id,
+            price: event.price as i64,
+            marketplace_fee: event.marketplace_fee as i64,
+            royalty_fee: event.royalty_fee as i64,
+            owner_wallet_address: event.owner,
+            kiosk_standard: event.kiosk_standard,
+            created_at: listed_at,
+            updated_at: listed_at,
+        },
+    )
+    .await?;
+    txn.commit().await?;
+
+    Ok(())
+}
+
+pub async fn on_item_updated(app_state: AppState, context: EventContext) -> Result<(), Error> {
+    let event: ItemUpdatedEvent = decode_marketplace(&context.event)?;
+    let db = app_state.db.as_ref();
+
+    let txn = db.begin().await?;
+    insert_nft_event(
+        &txn,
+        NewNftEvent {
+            kind: NftEventKind::UpdateListing,
+            token_id: event.token_id.clone(),
+            collection_type: event.collection_type.clone(),
+            digest: context.digest.clone(),
+            price: Some(event.price),
+            marketplace_fee: Some(event.marketplace_fee),
+            royalty_fee: Some(event.royalty_fee),
+            sender: context.sender.clone(),
+            receiver: None,
+            description: Some(context.event.parsed_json.clone()),
+            timestamp_ms: context.timestamp_ms,
+        },
+    )
+    .await?;
+
+    if has_newer_event(&txn, &event.token_id, LISTING_EVENTS, context.timestamp_ms).await? {
+        txn.commit().await?;
+        return Ok(());
+    }
+
+    let updated = update_listing_price(
+        &txn,
+        &event.shared_purchase_cap,
+        &event.token_id,
+        ListingPrice {
+            price: event.price,
+            marketplace_fee: event.marketplace_fee,
+            royalty_fee: event.royalty_fee,
+        },
+        event_time(context.timestamp_ms),
+    )
+    .await?;
+    txn.commit().await?;
+    if updated == 0 {
+        // The listing predates the indexed range
+        eprintln!(
+            "Price update for unknown listing {} of {} in {}",
+            event.shared_purchase_cap, event.token_id, context.digest
+        );
+    }
+
+    Ok(())
+}
+
+pub async fn on_item_delisted(app_state: AppState, context: EventContext) -> Result<(), Error> {
+    let event: ItemDelistedEvent = decode_marketplace(&context.event)?;
+    let db = app_state.db.as_ref();
+
+    let txn = db.begin().await?;
+    insert_nft_event(
+        &txn,
+        NewNftEvent {
+            kind: NftEventKind::Delist,
+            token_id: event.token_id.clone(),
+            collection_type: event.collection_type.clone(),
+            digest: context.digest.clone(),
+            price: None,
+            marketplace_fee: None,
+            royalty_fee: None,
+            sender: context.sender.clone(),
+            receiver: None,
+            description: Some(context.event.parsed_json.clone()),
+            timestamp_ms: context.timestamp_ms,
+        },
+    )
+    .await?;
+
+    if !has_newer_event(&txn, &event.token_id, LISTING_EVENTS, context.timestamp_ms).await? {
+        remove_listing(
+            &txn,
+            &event.shared_purchase_cap,
+            &event.token_id,
+            event_time(context.timestamp_ms),
+        )
+        .await?;
+    }
+    txn.commit().await?;
+
+    Ok(())
+}
+
+/// Records the purchase and its volume, and hands the NFT to the buyer, in one transaction.
+pub async fn on_item_bought(app_state: AppState, context: EventContext) -> Result<(), Error> {
+    let event: ItemBoughtEvent = decode_marketplace(&context.event)?;
+    let db = app_state.db.as_ref();
+
+    if !ensure_nft(&app_state, &event.token_id).await? {
+        eprintln!(
+            "Sale of {} in {} only counts towards the collection: the NFT has no display data",
+            event.token_id, context.digest
+        );
+    }
+
+    let txn = db.begin().await?;
+    let recorded = insert_nft_event(
+        &txn,
+        NewNftEvent {
+            kind: NftEventKind::Buy,
+            token_id: event.token_id.clone(),
+            collection_type: event.collection_type.clone(),
+            digest: context.digest.clone(),
+            price: Some(event.price),
+            marketplace_fee: Some(event.marketplace_fee),
+            royalty_fee: Some(event.royalty_fee),
+            sender: event.seller.clone(),
+            receiver: Some(event.buyer.clone()),
+            description: Some(context.event.parsed_json.clone()),
+            timestamp_ms: context.timestamp_ms,
+        },
+    )
+    .await?;
+
+    if !has_newer_event(&txn, &event.token_id, LISTING_EVENTS, context.timestamp_ms).await? {
+        remove_listing(
+            &txn,
+            &event.shared_purchase_cap,
+            &event.token_id,
+            event_time(context.timestamp_ms),
+        )
+        .await?;
+    }
+    // The listing kiosk is the seller's; where the buyer keeps the NFT is not part of the event
+    apply_sale(
+        &txn,
+        Sale {
+            token_id: &event.token_id,
+            collection_type: &event.collection_type,
+            buyer: &event.buyer,
+            kiosk: None,
+            price: event.price,
+            timestamp_ms: context.timestamp_ms,
+        },
+        recorded,
+    )
+    .await?;
+    txn.commit().await?;
+
+    Ok(())
+}
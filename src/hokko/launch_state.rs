@@ -0,0 +1,270 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{Error, bail};
+use chrono::NaiveDateTime;
+
+use crate::entity::launchpad_collection;
+
+/// Lifecycle of a launchpad collection, stored in `LaunchpadCollection.state`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LaunchState {
+    /// Registered by the creator, waiting for a Hokko admin.
+    Pending,
+    /// Approved and mintable during its phases.
+    Approved,
+    Rejected,
+    /// Paused by a Hokko admin. Only an admin can resume it.
+    Paused,
+    /// Paused by the creator.
+    CreatorPaused,
+    /// Paused by a Hokko admin while already paused by the creator. Lifting the admin
+    /// pause leaves the creator's pause in place.
+    PausedByBoth,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LaunchTransition {
+    Approve,
+    Reject,
+    Pause,
+    Resume,
+    CreatorPause,
+    CreatorResume,
+}
+
+impl LaunchState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LaunchState::Pending => "pending",
+            LaunchState::Approved => "approved",
+            LaunchState::Rejected => "rejected",
+            LaunchState::Paused => "paused",
+            LaunchState::CreatorPaused => "creator_paused",
+            LaunchState::PausedByBoth => "paused_by_both",
+        }
+    }
+
+    /// Applies `transition`, refusing anything the launchpad contracts would not allow.
+    pub fn apply(self, transition: LaunchTransition) -> Result<LaunchState, Error> {
+        use LaunchState::*;
+        use LaunchTransition::*;
+
+        let next = match (self, transition) {
+            (Pending, Approve) => Approved,
+            (Pending, Reject) => Rejected,
+            (Approved, Pause) => Paused,
+            (CreatorPaused, Pause) => PausedByBoth,
+            (Paused, Resume) => Approved,
+            (PausedByBoth, Resume) => CreatorPaused,
+            (Approved, CreatorPause) => CreatorPaused,
+            (CreatorPaused, CreatorResume) => Approved,
+            _ => bail!("cannot {transition} a {self} launch"),
+        };
+
+        Ok(next)
+    }
+}
+
+impl fmt::Display for LaunchState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Display for LaunchTransition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            LaunchTransition::Approve => "approve",
+            LaunchTransition::Reject => "reject",
+            LaunchTransition::Pause => "pause",
+            LaunchTransition::Resume => "resume",
+            LaunchTransition::CreatorPause => "creator-pause",
+            LaunchTransition::CreatorResume => "creator-resume",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for LaunchState {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "pending" => Ok(LaunchState::Pending),
+            "approved" => Ok(LaunchState::Approved),
+            "rejected" => Ok(LaunchState::Rejected),
+            "paused" => Ok(LaunchState::Paused),
+            "creator_paused" => Ok(LaunchState::CreatorPaused),
+            "paused_by_both" => Ok(LaunchState::PausedByBoth),
+            _ => bail!("unknown launch state `{value}`"),
+        }
+    }
+}
+
+/// Sale phase of a launch, each with its own price and supply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MintPhase {
+    Public,
+    Whitelist,
+    Custom,
+}
+
+impl MintPhase {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MintPhase::Public => "public",
+            MintPhase::Whitelist => "whitelist",
+            MintPhase::Custom => "custom",
+        }
+    }
+
+    /// Phase running at `at`: the enabled phase that started most recently. Phases run
+    /// until the next one starts, and the public phase runs until the launch sells out.
+    /// Returns `None` before the first phase starts.
+    pub fn active(launch: &launchpad_collection::Model, at: NaiveDateTime) -> Option<MintPhase> {
+        [
+            (MintPhase::Public, true, launch.start_time),
+            (
+                MintPhase::Whitelist,
+                launch.white_list_enabled,
+                launch.white_list_start_time,
+            ),
+            (
+                MintPhase::Custom,
+                launch.custom_enabled,
+                launch.custom_start_time,
+            ),
+        ]
+        .into_iter()
+        .filter(|(_, enabled, start)| *enabled && *start <= at)
+        .max_by_key(|(_, _, start)| *start)
+        .map(|(phase, _, _)| phase)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::DateTime;
+
+    use super::*;
+
+    fn time(seconds: i64) -> NaiveDateTime {
+        DateTime::from_timestamp(seconds, 0).unwrap().naive_utc()
+    }
+
+    fn launch() -> launchpad_collection::Model {
+        launchpad_collection::Model {
+            collection_type: "0xabc::nft::Nft".to_string(),
+            kiosk_standard: true,
+            name: "Sample".to_string(),
+            description: String::new(),
+            supply: "100".to_string(),
+            price: 10,
+            max_item_per_address: "5".to_string(),
+            white_list_enabled: true,
+            white_list_price: 5,
+            white_list_supply: "20".to_string(),
+            custom_enabled: false,
+            custom_name: String::new(),
+            custom_price: 0,
+            custom_supply: "0".to_string(),
+            mint_count: 0,
+            created_at: time(0),
+            updated_at: time(0),
+            creator_cap: "0xe2".to_string(),
+            launch_id: "0xe1".to_string(),
+            native: true,
+            state: LaunchState::Approved.as_str().to_string(),
+            cover_image: None,
+            start_time: time(200),
+            white_list_start_time: time(100),
+            custom_start_time: time(150),
+            owner_wallet_address: "0xb1".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_active_phase_follows_start_times() {
+        let launch = launch();
+
+        assert_eq!(MintPhase::active(&launch, time(50)), None);
+        assert_eq!(
+            MintPhase::active(&launch, time(120)),
+            Some(MintPhase::Whitelist)
+        );
+        // The custom phase is disabled, so the whitelist phase keeps running
+        assert_eq!(
+            MintPhase::active(&launch, time(160)),
+            Some(MintPhase::Whitelist)
+        );
+        assert_eq!(
+            MintPhase::active(&launch, time(200)),
+            Some(MintPhase::Public)
+        );
+    }
+
+    #[test]
+    fn test_happy_path() {
+        let state = LaunchState::Pending
+            .apply(LaunchTransition::Approve)
+            .unwrap()
+            .apply(LaunchTransition::Pause)
+            .unwrap()
+            .apply(LaunchTransition::Resume)
+            .unwrap();
+
+        assert_eq!(state, LaunchState::Approved);
+    }
+
+    #[test]
+    fn test_admin_resume_keeps_creator_pause() {
+        let state = LaunchState::CreatorPaused
+            .apply(LaunchTransition::Pause)
+            .unwrap();
+        assert_eq!(state, LaunchState::PausedByBoth);
+        // The creator cannot lift the admin pause
+        assert!(state.apply(LaunchTransition::CreatorResume).is_err());
+
+        let state = state.apply(LaunchTransition::Resume).unwrap();
+        assert_eq!(state, LaunchState::CreatorPaused);
+        assert_eq!(
+            state.apply(LaunchTransition::CreatorResume).unwrap(),
+            LaunchState::Approved
+        );
+    }
+
+    #[test]
+    fn test_rejects_illegal_transitions() {
+        let error = LaunchState::Rejected
+            .apply(LaunchTransition::Resume)
+            .unwrap_err();
+        assert_eq!(error.to_string(), "cannot resume a rejected launch");
+
+        // Only an admin can lift an admin pause
+        assert!(
+            LaunchState::Paused
+                .apply(LaunchTransition::CreatorResume)
+                .is_err()
+        );
+        assert!(
+            LaunchState::Approved
+                .apply(LaunchTransition::Approve)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_round_trips_through_strings() {
+        for state in [
+            LaunchState::Pending,
+            LaunchState::Approved,
+            LaunchState::Rejected,
+            LaunchState::Paused,
+            LaunchState::CreatorPaused,
+            LaunchState::PausedByBoth,
+        ] {
+            assert_eq!(state.as_str().parse::<LaunchState>().unwrap(), state);
+        }
+    }
+}
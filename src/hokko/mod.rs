@@ -0,0 +1,135 @@
+pub mod dispatcher;
+pub mod events;
+pub mod handlers;
+pub mod launch_state;
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::constants::contract::{
+    HokkoEventTypes, HokkoPackageId, KioskEventTypes, MarketplaceVersion,
+};
+use crate::hokko::dispatcher::{Dispatcher, canonical_type};
+use crate::hokko::handlers::{collection_escrow, escrow, kiosk, launchpad, marketplace, trade};
+
+/// Dispatcher with a handler for every Hokko event the indexer understands, plus framework
+/// kiosk events of the `tracked` collections.
+pub fn dispatcher(tracked: &[String]) -> Dispatcher {
+    let mut dispatcher = Dispatcher::new(HokkoPackageId::hokko_hash_set());
+
+    // Upgrades keep emitting the events of the original package, but each version is
+    // matched so independently published versions are indexed as well
+    for version in MarketplaceVersion::ALL {
+        dispatcher.register(
+            HokkoEventTypes::kiosk_created(version),
+            marketplace::on_kiosk_created,
+        );
+        dispatcher.register(
+            HokkoEventTypes::personal_fee_updated(version),
+            marketplace::on_personal_fee_set,
+        );
+
+        dispatcher.register(
+            HokkoEventTypes::listing_created(version),
+            trade::on_item_listed,
+        );
+        dispatcher.register(
+            HokkoEventTypes::listing_updated(version),
+            trade::on_item_updated,
+        );
+        dispatcher.register(HokkoEventTypes::delisted(version), trade::on_item_delisted);
+        dispatcher.register(HokkoEventTypes::purchased(version), trade::on_item_bought);
+
+        dispatcher.register(HokkoEventTypes::offer_created(version), escrow::on_offer);
+        dispatcher.register(
+            HokkoEventTypes::offer_accepted(version),
+            escrow::on_offer_accepted,
+        );
+        dispatcher.register(
+            HokkoEventTypes::offer_declined(version),
+            escrow::on_offer_declined,
+        );
+        dispatcher.register(
+            HokkoEventTypes::offer_revoked(version),
+            escrow::on_offer_revoked,
+        );
+        dispatcher.register(
+            HokkoEventTypes::recipient_created(version),
+            escrow::on_receipt_created,
+        );
+        dispatcher.register(
+            HokkoEventTypes::recipient_destroyed(version),
+            escrow::on_receipt_destroyed,
+        );
+
+        dispatcher.register(
+            HokkoEventTypes::collection_offer_created(version),
+            collection_escrow::on_new_offer,
+        );
+        dispatcher.register(
+            HokkoEventTypes::collection_offer_accepted(version),
+            collection_escrow::on_offer_accepted,
+        );
+        dispatcher.register(
+            HokkoEventTypes::collection_offer_revoked(version),
+            collection_escrow::on_offer_revoked,
+        );
+        dispatcher.register(
+            HokkoEventTypes::recipient_created_collection(version),
+            escrow::on_receipt_created,
+        );
+        dispatcher.register(
+            HokkoEventTypes::recipient_destroyed_collection(version),
+            escrow::on_receipt_destroyed,
+        );
+    }
+
+    dispatcher.register(
+        HokkoEventTypes::creator_initialized(),
+        launchpad::on_launch_initialized,
+    );
+    dispatcher.register(
+        HokkoEventTypes::approve_collection(),
+        launchpad::on_approved,
+    );
+    dispatcher.register(
+        HokkoEventTypes::rejected_collection(),
+        launchpad::on_rejected,
+    );
+    dispatcher.register(HokkoEventTypes::paused_collection(), launchpad::on_paused);
+    dispatcher.register(HokkoEventTypes::resumed_collection(), launchpad::on_resumed);
+    dispatcher.register(
+        HokkoEventTypes::creator_paused(),
+        launchpad::on_creator_paused,
+    );
+    dispatcher.register(
+        HokkoEventTypes::creator_resumed(),
+        launchpad::on_creator_resumed,
+    );
+    dispatcher.register(
+        HokkoEventTypes::creator_timestamp_updated(),
+        launchpad::on_timestamps_updated,
+    );
+    dispatcher.register(
+        HokkoEventTypes::creator_whitelist_updated(),
+        launchpad::on_whitelist_updated,
+    );
+    dispatcher.register(HokkoEventTypes::item_minted(), launchpad::on_item_minted);
+
+    let tracked: Arc<HashSet<String>> =
+        Arc::new(tracked.iter().map(|item| canonical_type(item)).collect());
+    dispatcher.register(KioskEventTypes::item_listed(), {
+        let tracked = tracked.clone();
+        move |app_state, context| kiosk::on_item_listed(app_state, context, tracked.clone())
+    });
+    dispatcher.register(KioskEventTypes::item_delisted(), {
+        let tracked = tracked.clone();
+        move |app_state, context| kiosk::on_item_delisted(app_state, context, tracked.clone())
+    });
+    dispatcher.register(
+        KioskEventTypes::item_purchased(),
+        move |app_state, context| kiosk::on_item_purchased(app_state, context, tracked.clone()),
+    );
+
+    dispatcher
+}
@@ -0,0 +1,88 @@
+use anyhow::{Error, anyhow, bail};
+
+/// How the indexer was asked to run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mode {
+    /// Follow the chain from the persisted live cursor.
+    Live,
+    /// Re-index the inclusive checkpoint range `from..=to`, then exit.
+    Backfill { from: u64, to: u64 },
+}
+
+/// Parses `rust_indexer [backfill --from <checkpoint> --to <checkpoint>]`.
+///
+/// `args` must not contain the program name.
+pub fn parse_args<I>(args: I) -> Result<Mode, Error>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter();
+
+    match args.next().as_deref() {
+        None => Ok(Mode::Live),
+        Some("backfill") => parse_backfill(args),
+        Some(other) => bail!("Unknown command `{other}`, expected `backfill`"),
+    }
+}
+
+fn parse_backfill(mut args: impl Iterator<Item = String>) -> Result<Mode, Error> {
+    let mut from = None;
+    let mut to = None;
+
+    while let Some(flag) = args.next() {
+        let target = match flag.as_str() {
+            "--from" => &mut from,
+            "--to" => &mut to,
+            _ => bail!("Unknown backfill argument `{flag}`"),
+        };
+        let value = args
+            .next()
+            .ok_or_else(|| anyhow!("Missing checkpoint after `{flag}`"))?;
+        let checkpoint = value
+            .parse::<u64>()
+            .map_err(|e| anyhow!("Invalid checkpoint `{value}` for `{flag}`: {e}"))?;
+        *target = Some(checkpoint);
+    }
+
+    let from = from.ok_or_else(|| anyhow!("Backfill requires `--from <checkpoint>`"))?;
+    let to = to.ok_or_else(|| anyhow!("Backfill requires `--to <checkpoint>`"))?;
+    // Cursors name the last committed checkpoint, so there is no cursor before genesis.
+    // Checkpoint 0 only holds the genesis transaction and has nothing to index.
+    if from == 0 {
+        bail!("Backfill `--from` must be at least 1");
+    }
+    if from > to {
+        bail!("Backfill `--from` ({from}) must not be greater than `--to` ({to})");
+    }
+
+    Ok(Mode::Backfill { from, to })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_live_without_arguments() {
+        assert_eq!(parse_args(args(&[])).unwrap(), Mode::Live);
+    }
+
+    #[test]
+    fn test_parse_backfill_range() {
+        let mode = parse_args(args(&["backfill", "--to", "120", "--from", "100"])).unwrap();
+        assert_eq!(mode, Mode::Backfill { from: 100, to: 120 });
+    }
+
+    #[test]
+    fn test_parse_backfill_rejects_bad_ranges() {
+        assert!(parse_args(args(&["backfill", "--from", "100"])).is_err());
+        assert!(parse_args(args(&["backfill", "--from", "12x", "--to", "20"])).is_err());
+        assert!(parse_args(args(&["backfill", "--from", "30", "--to", "20"])).is_err());
+        assert!(parse_args(args(&["backfill", "--from", "0", "--to", "20"])).is_err());
+        assert!(parse_args(args(&["reindex"])).is_err());
+    }
+}
@@ -0,0 +1,5 @@
+pub mod cli;
+pub mod pipeline;
+pub mod shutdown;
+pub mod stages;
+pub mod tip;
@@ -0,0 +1,325 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anyhow::{Error, bail};
+use chrono::Utc;
+use sui_sdk::rpc_types::SuiObjectResponse;
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
+use tokio::time::Instant;
+
+use crate::AppState;
+use crate::hokko::dispatcher::Dispatcher;
+use crate::indexer::shutdown::Shutdown;
+use crate::indexer::stages::{CommitTracker, Sequencer, spawn_workers};
+use crate::indexer::tip::TipFollower;
+use crate::processor;
+use crate::processor::model::NftData;
+use crate::source::CheckpointData;
+use crate::store::collection_stats::refresh_volume_windows;
+use crate::store::cursor::save_cursor;
+use crate::store::store::store_nft;
+
+/// Packages and event handlers shared by every pipeline run.
+pub struct Filters {
+    pub whitelisted_packages: HashSet<String>,
+    pub dispatcher: Dispatcher,
+}
+
+/// A named run over the checkpoint stream whose progress is stored under `name`.
+pub struct Pipeline {
+    pub name: String,
+    /// Last committed checkpoint; indexing resumes at the one after it.
+    pub cursor: u64,
+    /// Inclusive upper bound. `None` follows the chain indefinitely.
+    pub end: Option<u64>,
+}
+
+impl Pipeline {
+    fn is_finished(&self) -> bool {
+        self.end.is_some_and(|end| self.cursor >= end)
+    }
+
+    fn page_limit(&self, limit: usize) -> usize {
+        match self.end {
+            Some(end) => limit.min(end.saturating_sub(self.cursor) as usize),
+            None => limit,
+        }
+    }
+}
+
+/// Output of the filter stage: the objects worth assembling for one checkpoint.
+struct Filtered {
+    sequence_number: u64,
+    objects: Vec<SuiObjectResponse>,
+}
+
+/// Output of the assemble stage: the NFTs to store for one checkpoint.
+struct Assembled {
+    sequence_number: u64,
+    nfts: Vec<NftData>,
+}
+
+/// Runs the pipeline as fetch → filter → assemble → store stages connected by bounded
+/// channels, followed by a committer that persists the cursor once every checkpoint up
+/// to it has been stored. Returns once `end` is reached, `shutdown` has drained the
+/// stages, or any stage fails.
+pub async fn run(
+    app_state: AppState,
+    filters: Arc<Filters>,
+    pipeline: Pipeline,
+    mut shutdown: Shutdown,
+) -> Result<(), Error> {
+    let settings = app_state.config.pipeline.clone();
+    let mut tasks = JoinSet::new();
+
+    let (checkpoint_tx, checkpoint_rx) = mpsc::channel(settings.channel_capacity);
+    let (filtered_tx, filtered_rx) = mpsc::channel(settings.channel_capacity);
+    let (assembled_tx, assembled_rx) = mpsc::channel(settings.channel_capacity);
+    let (stored_tx, stored_rx) = mpsc::channel(settings.channel_capacity);
+
+    let shared_filters = filters.clone();
+    let sequencer = Arc::new(Sequencer::new(pipeline.cursor));
+    let state = app_state.clone();
+    spawn_workers(
+        &mut tasks,
+        settings.filter_workers,
+        checkpoint_rx,
+        filtered_tx,
+        move |data: CheckpointData| {
+            let app_state = state.clone();
+            let filters = filters.clone();
+            let sequencer = sequencer.clone();
+            async move {
+                let sequence_number = data.checkpoint.sequence_number;
+
+                // Marketplace and launchpad state is derived from event order
+                sequencer.turn(sequence_number).await;
+                processor::procesor::dispatch_events(
+                    &app_state,
+                    &data.transactions,
+                    data.checkpoint.timestamp_ms,
+                    &filters.dispatcher,
+                )
+                .await?;
+                sequencer.done(sequence_number);
+
+                let objects = processor::procesor::find_potential_nfts(
+                    app_state,
+                    data.transactions,
+                    &filters.whitelisted_packages,
+                )
+                .await?;
+                Ok(Filtered {
+                    sequence_number,
+                    objects,
+                })
+            }
+        },
+    );
+
+    let state = app_state.clone();
+    spawn_workers(
+        &mut tasks,
+        settings.assemble_workers,
+        filtered_rx,
+        assembled_tx,
+        move |filtered: Filtered| {
+            let app_state = state.clone();
+            async move {
+                // println!("Potential Nfts: {:#?}", filtered.objects);
+                let nfts =
+                    processor::assemble::assemble_objects(&app_state, filtered.objects).await?;
+                Ok(Assembled {
+                    sequence_number: filtered.sequence_number,
+                    nfts,
+                })
+            }
+        },
+    );
+
+    let state = app_state.clone();
+    spawn_workers(
+        &mut tasks,
+        settings.store_workers,
+        assembled_rx,
+        stored_tx,
+        move |assembled: Assembled| {
+            let app_state = state.clone();
+            async move {
+                for nft in assembled.nfts {
+                    store_nft(app_state.clone(), nft).await?;
+                }
+                Ok(assembled.sequence_number)
+            }
+        },
+    );
+
+    let committed = Arc::new(AtomicU64::new(pipeline.cursor));
+    tasks.spawn(commit_checkpoints(
+        app_state.clone(),
+        pipeline.name.clone(),
+        committed.clone(),
+        stored_rx,
+    ));
+    tasks.spawn(fetch_checkpoints(
+        app_state,
+        pipeline,
+        committed,
+        checkpoint_tx,
+        shutdown.clone(),
+    ));
+
+    let drain_timeout = settings.shutdown_timeout();
+    let mut drain_deadline: Option<Instant> = None;
+    loop {
+        let drain = tokio::time::sleep_until(drain_deadline.unwrap_or_else(Instant::now));
+        let joined = tokio::select! {
+            joined = tasks.join_next() => joined,
+            _ = shutdown.wait(), if drain_deadline.is_none() => {
+                println!("Draining in-flight checkpoints for at most {drain_timeout:?}");
+                drain_deadline = Some(Instant::now() + drain_timeout);
+                continue;
+            }
+            _ = drain, if drain_deadline.is_some() => {
+                tasks.abort_all();
+                bail!(
+                    "Timed out draining the pipeline after {drain_timeout:?}; \
+                     checkpoints past the committed cursor will be indexed again"
+                );
+            }
+        };
+        let Some(result) = joined else {
+            break;
+        };
+
+        if let Err(e) = result.map_err(Error::from).and_then(|result| result) {
+            // Nothing past the committed cursor is lost: it is indexed again on restart
+            tasks.abort_all();
+            return Err(e);
+        }
+    }
+
+    let unknown = shared_filters.dispatcher.unknown_count();
+    if unknown > 0 {
+        eprintln!("Skipped {unknown} Hokko events without a handler");
+    }
+    Ok(())
+}
+
+/// Feeds checkpoints into the pipeline. Blocks whenever the filter stage is saturated and
+/// stops as soon as `shutdown` triggers, which lets the later stages drain and finish.
+/// At the chain head it keeps polling with a backoff until new checkpoints appear.
+async fn fetch_checkpoints(
+    app_state: AppState,
+    mut pipeline: Pipeline,
+    committed: Arc<AtomicU64>,
+    checkpoint_tx: mpsc::Sender<CheckpointData>,
+    mut shutdown: Shutdown,
+) -> Result<(), Error> {
+    let mut tip = TipFollower::new(&app_state.config.tip);
+    let stats_refresh_interval = app_state.config.pipeline.stats_refresh_interval();
+    let mut last_stats_refresh: Option<Instant> = None;
+
+    while !pipeline.is_finished() && !shutdown.is_triggered() {
+        if tip.lag_report_due() {
+            report_lag(
+                &app_state,
+                &pipeline.name,
+                committed.load(Ordering::Relaxed),
+            )
+            .await;
+        }
+        if last_stats_refresh.is_none_or(|last| last.elapsed() >= stats_refresh_interval) {
+            last_stats_refresh = Some(Instant::now());
+            refresh_stats(&app_state).await;
+        }
+
+        let limit = pipeline.page_limit(app_state.config.batch.checkpoints);
+        let next = app_state.source.next_checkpoints(pipeline.cursor, limit);
+        let checkpoints = tokio::select! {
+            checkpoints = next => checkpoints?,
+            _ = shutdown.wait() => break,
+        };
+
+        if tip.observe(checkpoints.len()) {
+            println!(
+                "Caught up with the chain at checkpoint {} ({})",
+                pipeline.cursor, pipeline.name
+            );
+        }
+        if checkpoints.is_empty() {
+            let delay = tokio::time::sleep(tip.poll.idle());
+            tokio::select! {
+                _ = delay => continue,
+                _ = shutdown.wait() => break,
+            }
+        }
+        println!(
+            "Processed Checkpoint {} ({})",
+            pipeline.cursor, pipeline.name
+        );
+
+        for data in checkpoints {
+            let sequence_number = data.checkpoint.sequence_number;
+            if pipeline.end.is_some_and(|end| sequence_number > end) {
+                return Ok(());
+            }
+            let sent = tokio::select! {
+                sent = checkpoint_tx.send(data) => sent.is_ok(),
+                _ = shutdown.wait() => break,
+            };
+            if !sent {
+                // A later stage stopped; its error is reported by `run`
+                return Ok(());
+            }
+            pipeline.cursor = sequence_number;
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints how far the committed cursor trails the newest checkpoint of the network.
+/// A failed lookup only skips this report; it never stops indexing.
+async fn report_lag(app_state: &AppState, name: &str, committed: u64) {
+    match app_state.source.latest_checkpoint().await {
+        Ok(latest) => println!(
+            "Pipeline {name} lag: {} checkpoints (latest {latest}, committed {committed})",
+            latest.saturating_sub(committed)
+        ),
+        Err(e) => eprintln!("Could not fetch the latest checkpoint for lag reporting: {e:#}"),
+    }
+}
+
+/// Lets the rolling volume windows move on while no sales are indexed. Like the lag report,
+/// a failure only skips this refresh.
+async fn refresh_stats(app_state: &AppState) {
+    let now = Utc::now().naive_utc();
+    if let Err(e) = refresh_volume_windows(app_state.db.as_ref(), now).await {
+        eprintln!("Could not refresh collection volume windows: {e}");
+    }
+}
+
+/// Persists the cursor as checkpoints finish the store stage, never skipping over a
+/// checkpoint that is still in flight.
+async fn commit_checkpoints(
+    app_state: AppState,
+    name: String,
+    committed: Arc<AtomicU64>,
+    mut stored_rx: mpsc::Receiver<u64>,
+) -> Result<(), Error> {
+    let mut tracker = CommitTracker::new(committed.load(Ordering::Relaxed));
+
+    while let Some(sequence_number) = stored_rx.recv().await {
+        if let Some(cursor) = tracker.complete(sequence_number) {
+            save_cursor(app_state.db.as_ref(), &name, cursor).await?;
+            committed.store(cursor, Ordering::Relaxed);
+        }
+    }
+
+    // Every advance was saved as it happened, so this is the cursor a restart resumes from
+    println!("Pipeline {name} reached checkpoint {}", tracker.cursor());
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use tokio::sync::watch;
+
+/// Cloneable handle that resolves once the process has been asked to stop.
+#[derive(Clone)]
+pub struct Shutdown {
+    receiver: watch::Receiver<bool>,
+}
+
+impl Shutdown {
+    /// A handle together with the sender that triggers it, for callers that decide
+    /// themselves when to stop.
+    pub fn new() -> (watch::Sender<bool>, Self) {
+        let (sender, receiver) = watch::channel(false);
+        (sender, Self { receiver })
+    }
+
+    /// A handle triggered by Ctrl-C or SIGTERM.
+    pub fn on_signals() -> Self {
+        let (sender, shutdown) = Self::new();
+        tokio::spawn(async move {
+            wait_for_signal().await;
+            println!("Shutdown signal received");
+            let _ = sender.send(true);
+        });
+        shutdown
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        *self.receiver.borrow()
+    }
+
+    pub async fn wait(&mut self) {
+        // An error means the sender is gone without ever triggering, so never resolve
+        if self
+            .receiver
+            .wait_for(|triggered| *triggered)
+            .await
+            .is_err()
+        {
+            std::future::pending::<()>().await;
+        }
+    }
+}
+
+#[cfg(unix)]
+async fn wait_for_signal() {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut terminate = signal(SignalKind::terminate()).expect("SIGTERM handler can be installed");
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = terminate.recv() => {}
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal() {
+    let _ = tokio::signal::ctrl_c().await;
+}
@@ -0,0 +1,166 @@
+use std::collections::BTreeSet;
+use std::future::Future;
+use std::sync::Arc;
+
+use anyhow::Error;
+use tokio::sync::{Mutex, mpsc, watch};
+use tokio::task::JoinSet;
+
+/// Spawns `workers` tasks that take items from `input`, run `stage` on them and pass the
+/// result to `output`. Sending blocks while `output` is full, which is what propagates
+/// backpressure up to the checkpoint fetcher. Workers stop when `input` is closed or the
+/// next stage has gone away.
+pub fn spawn_workers<I, O, F, Fut>(
+    tasks: &mut JoinSet<Result<(), Error>>,
+    workers: usize,
+    input: mpsc::Receiver<I>,
+    output: mpsc::Sender<O>,
+    stage: F,
+) where
+    I: Send + 'static,
+    O: Send + 'static,
+    F: Fn(I) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = Result<O, Error>> + Send,
+{
+    let input = Arc::new(Mutex::new(input));
+    for _ in 0..workers {
+        let input = input.clone();
+        let output = output.clone();
+        let stage = stage.clone();
+
+        tasks.spawn(async move {
+            loop {
+                let Some(item) = input.lock().await.recv().await else {
+                    break;
+                };
+                let result = stage(item).await?;
+                if output.send(result).await.is_err() {
+                    break;
+                }
+            }
+            Ok(())
+        });
+    }
+}
+
+/// Tracks checkpoints finished out of order by parallel workers and only moves the
+/// cursor across a contiguous run of finished checkpoints.
+pub struct CommitTracker {
+    cursor: u64,
+    completed: BTreeSet<u64>,
+}
+
+impl CommitTracker {
+    pub fn new(cursor: u64) -> Self {
+        Self {
+            cursor,
+            completed: BTreeSet::new(),
+        }
+    }
+
+    pub fn cursor(&self) -> u64 {
+        self.cursor
+    }
+
+    /// Marks `sequence_number` as fully stored. Returns the new cursor when it advanced.
+    pub fn complete(&mut self, sequence_number: u64) -> Option<u64> {
+        self.completed.insert(sequence_number);
+
+        let start = self.cursor;
+        while self.completed.remove(&(self.cursor + 1)) {
+            self.cursor += 1;
+        }
+        (self.cursor != start).then_some(self.cursor)
+    }
+}
+
+/// Lets parallel workers run one section of their work in checkpoint order. Every
+/// checkpoint after `cursor` must pass through exactly once, otherwise later ones wait
+/// forever.
+pub struct Sequencer {
+    next: watch::Sender<u64>,
+}
+
+impl Sequencer {
+    pub fn new(cursor: u64) -> Self {
+        let (next, _) = watch::channel(cursor + 1);
+        Self { next }
+    }
+
+    /// Waits until every checkpoint before `sequence_number` has called [`Sequencer::done`].
+    pub async fn turn(&self, sequence_number: u64) {
+        let mut next = self.next.subscribe();
+        // The sender lives in `self`, so the channel cannot close while we wait
+        let _ = next.wait_for(|next| *next >= sequence_number).await;
+    }
+
+    pub fn done(&self, sequence_number: u64) {
+        self.next
+            .send_modify(|next| *next = (*next).max(sequence_number + 1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commit_tracker_waits_for_gaps() {
+        let mut tracker = CommitTracker::new(100);
+
+        assert_eq!(tracker.complete(102), None);
+        assert_eq!(tracker.complete(103), None);
+        assert_eq!(tracker.complete(101), Some(103));
+        assert_eq!(tracker.complete(104), Some(104));
+        assert_eq!(tracker.cursor(), 104);
+    }
+
+    #[tokio::test]
+    async fn test_workers_process_every_item() {
+        let mut tasks = JoinSet::new();
+        let (input_tx, input_rx) = mpsc::channel(2);
+        let (output_tx, mut output_rx) = mpsc::channel(2);
+
+        spawn_workers(&mut tasks, 3, input_rx, output_tx, |item: u64| async move {
+            Ok(item * 2)
+        });
+        tokio::spawn(async move {
+            for item in 0..10 {
+                input_tx.send(item).await.unwrap();
+            }
+        });
+
+        let mut results = Vec::new();
+        while let Some(result) = output_rx.recv().await {
+            results.push(result);
+        }
+        results.sort();
+        assert_eq!(results, (0..10).map(|item| item * 2).collect::<Vec<_>>());
+
+        while let Some(result) = tasks.join_next().await {
+            result.unwrap().unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sequencer_orders_turns() {
+        let sequencer = Arc::new(Sequencer::new(10));
+        let order = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let mut tasks = JoinSet::new();
+        for sequence_number in (11..=15).rev() {
+            let sequencer = sequencer.clone();
+            let order = order.clone();
+            tasks.spawn(async move {
+                sequencer.turn(sequence_number).await;
+                order.lock().unwrap().push(sequence_number);
+                sequencer.done(sequence_number);
+            });
+        }
+        while let Some(result) = tasks.join_next().await {
+            result.unwrap();
+        }
+
+        assert_eq!(*order.lock().unwrap(), vec![11, 12, 13, 14, 15]);
+    }
+}
@@ -0,0 +1,109 @@
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use crate::config::settings::TipConfig;
+
+/// Poll interval used once the pipeline has caught up with the chain head. Starts at the
+/// minimum and doubles on every empty poll, so a quiet chain is not hammered while new
+/// checkpoints are still picked up quickly after a burst.
+pub struct PollInterval {
+    min: Duration,
+    max: Duration,
+    current: Duration,
+}
+
+impl PollInterval {
+    pub fn new(min: Duration, max: Duration) -> Self {
+        Self {
+            min,
+            max,
+            current: min,
+        }
+    }
+
+    /// Delay before the next poll after one that returned nothing.
+    pub fn idle(&mut self) -> Duration {
+        let delay = self.current;
+        self.current = (self.current * 2).min(self.max);
+        delay
+    }
+
+    /// New checkpoints arrived: poll eagerly again.
+    pub fn reset(&mut self) {
+        self.current = self.min;
+    }
+}
+
+/// Tracks whether the pipeline is at the chain head and when lag was last reported.
+pub struct TipFollower {
+    pub poll: PollInterval,
+    caught_up: bool,
+    lag_report_interval: Duration,
+    last_lag_report: Option<Instant>,
+}
+
+impl TipFollower {
+    pub fn new(config: &TipConfig) -> Self {
+        Self {
+            poll: PollInterval::new(config.min_poll_interval(), config.max_poll_interval()),
+            caught_up: false,
+            lag_report_interval: config.lag_report_interval(),
+            last_lag_report: None,
+        }
+    }
+
+    /// Records the outcome of a poll. Returns `true` on the transition into the caught-up
+    /// state, so it is logged once instead of on every empty poll.
+    pub fn observe(&mut self, fetched: usize) -> bool {
+        if fetched == 0 {
+            let reached = !self.caught_up;
+            self.caught_up = true;
+            reached
+        } else {
+            self.caught_up = false;
+            self.poll.reset();
+            false
+        }
+    }
+
+    pub fn lag_report_due(&mut self) -> bool {
+        let now = Instant::now();
+        let due = self
+            .last_lag_report
+            .is_none_or(|last| now.duration_since(last) >= self.lag_report_interval);
+        if due {
+            self.last_lag_report = Some(now);
+        }
+        due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_poll_interval_backs_off_and_resets() {
+        let mut poll = PollInterval::new(Duration::from_millis(100), Duration::from_millis(350));
+
+        assert_eq!(poll.idle(), Duration::from_millis(100));
+        assert_eq!(poll.idle(), Duration::from_millis(200));
+        assert_eq!(poll.idle(), Duration::from_millis(350));
+        assert_eq!(poll.idle(), Duration::from_millis(350));
+
+        poll.reset();
+        assert_eq!(poll.idle(), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_caught_up_is_reported_once_per_transition() {
+        let mut follower = TipFollower::new(&TipConfig::default());
+
+        assert!(!follower.observe(10));
+        assert!(follower.observe(0));
+        assert!(!follower.observe(0));
+        assert!(!follower.observe(3));
+        assert!(follower.observe(0));
+    }
+}
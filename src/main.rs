@@ -1,155 +1,129 @@
 use sea_orm::DatabaseConnection;
-use sui_sdk::{
-    SuiClient, SuiClientBuilder,
-    rpc_types::{Checkpoint, SuiObjectDataOptions, SuiTransactionBlockResponseOptions},
-    types::{digests::TransactionDigest, sui_serde::BigInt},
-};
 
 use std::{collections::HashSet, sync::Arc};
 
-use crate::constants::contract::{HokkoPackageId, WHITE_LISTED_PACKAGES};
-
-const LIMIT: usize = 50;
-// const MAX_QUERY_LIMIT: usize = 50;
-// const API_URL: &str =
-//     "https://hokko-sui-mainnet.n.dwellir.com/c0c81069-123b-42e2-83d9-75ee1137e544";
-const API_URL: &str = "https://weathered-soft-frost.sui-mainnet.quiknode.pro/c39d95c17cf68776ca7e369b9c4539681d7729c8/";
+use crate::config::settings::Config;
+use crate::indexer::cli::{Mode, parse_args};
+use crate::indexer::pipeline::{Filters, Pipeline};
+use crate::indexer::shutdown::Shutdown;
+use crate::source::{
+    CheckpointSource,
+    file::FileCheckpointSource,
+    replay::{RecordingCheckpointSource, ReplayCheckpointSource},
+    rpc::RpcCheckpointSource,
+};
+use crate::store::cursor::{LIVE_PIPELINE, load_cursor};
 
+mod config;
 mod constants;
 mod entity;
+mod hokko;
+mod indexer;
 mod processor;
+mod source;
 mod store;
 
 #[derive(Clone)]
 pub struct AppState {
     pub db: Arc<DatabaseConnection>,
-    pub client: Arc<SuiClient>,
+    pub source: Arc<dyn CheckpointSource>,
+    pub config: Arc<Config>,
 }
 
 #[tokio::main]
 async fn main() -> Result<(), anyhow::Error> {
+    let mode = parse_args(std::env::args().skip(1))?;
+
     // Database connection
     dotenv::dotenv().ok();
 
+    let config = Arc::new(Config::load()?);
+    println!(
+        "Indexing {:?} through {:?}",
+        config.network,
+        config.rpc_endpoints()
+    );
+
     let database_url = std::env::var("DATABASE_URL")
         .expect("DATABASE_URL must be set in .env file or environment variable");
 
     let db = Arc::new(sea_orm::Database::connect(&database_url).await?);
 
-    // 193,710,202
-    // let mut initial_cursor: BigInt<u64> = BigInt::from(193135143);
-    let mut initial_cursor: BigInt<u64> = BigInt::from(193710192);
+    let pipeline = match mode {
+        Mode::Live => {
+            let cursor = match load_cursor(db.as_ref(), LIVE_PIPELINE).await? {
+                Some(checkpoint) => {
+                    println!("Resuming {LIVE_PIPELINE} pipeline after checkpoint {checkpoint}");
+                    checkpoint
+                }
+                None => config.start_checkpoint,
+            };
+            Pipeline {
+                name: LIVE_PIPELINE.to_string(),
+                cursor,
+                end: None,
+            }
+        }
+        Mode::Backfill { from, to } => {
+            // Each range keeps its own cursor so an interrupted backfill resumes where it stopped
+            // and never moves the live cursor
+            let name = format!("backfill_{from}_{to}");
+            let cursor = load_cursor(db.as_ref(), &name).await?.unwrap_or(from - 1);
+            println!("Backfilling checkpoints {from}..={to} after checkpoint {cursor}");
+            Pipeline {
+                name,
+                cursor,
+                end: Some(to),
+            }
+        }
+    };
+
     let mut whitelisted_packages: HashSet<String> = HashSet::new();
-    let hokko_packages: HashSet<String> = HokkoPackageId::hokko_hash_set();
 
-    for package in WHITE_LISTED_PACKAGES {
+    for package in &config.collections.tracked {
         println!("packge: {package:?}");
         whitelisted_packages.insert(package.to_string());
     }
 
-    // let client = SuiClientBuilder::default().build_mainnet().await?;
-    let client = Arc::new(SuiClientBuilder::default().build(API_URL).await?);
+    let source = build_source(&config).await?;
 
     let app_state = AppState {
         db: db.clone(),
-        client: client.clone(),
+        source,
+        config,
     };
 
-    let option_transaction = SuiTransactionBlockResponseOptions::new()
-        .with_events()
-        .with_effects()
-        .with_object_changes();
-
-    let option_object = SuiObjectDataOptions::new()
-        .with_content()
-        .with_type()
-        .with_display()
-        .with_owner();
-
-    loop {
-        let checkpoints = get_checkpoints(app_state.client.clone(), &mut initial_cursor).await?;
-        println!("Processed Checkpoint {initial_cursor}");
-
-        for checkpoint in checkpoints {
-            let digests = checkpoint.transactions;
-
-            if digests.len() < 50 {
-                let potential_nfts = processor::procesor::find_potential_nfts(
-                    app_state.clone(),
-                    digests,
-                    option_transaction.clone(),
-                    option_object.clone(),
-                    &whitelisted_packages,
-                    &hokko_packages,
-                )
-                .await?;
-                if potential_nfts.len() == 0 {
-                    continue;
-                }
-                //
-                // println!("Potential Nfts: {potential_nfts:#?}");
-                let app_state = app_state.clone();
-
-                tokio::task::spawn(async move {
-                    processor::assemble::process_object(app_state, potential_nfts).await
-                });
-            } else {
-                // let partitions = partition_transactions_by_limit_of(&digests, MAX_QUERY_LIMIT);
-                // let tasks: Vec<_> = partitions
-                //     .iter()
-                //     .map(|vec_tx| {
-                //         let options = options_transaction.clone();
-                //         let client = client.clone();
-                //         // let txs = vec_tx.clone();
-                //
-                //         tokio::task::spawn(async move {
-                //             client
-                //                 .read_api()
-                //                 .multi_get_transactions_with_options(vec_tx.to_vec(), options)
-                //                 .await
-                //         })
-                //     })
-                //     .collect();
-                //
-                // let results = futures::future::try_join_all(tasks).await?;
-                //
-                // let objects: Vec<SuiTransactionBlockResponse> = results
-                //     .into_iter()
-                //     .filter_map(|result| result.ok())
-                //     .flatten()
-                //     .collect();
-                // println!("Objects {objects:#?}");
-            }
-
-            // checkpoint.transactions.iter().for_each(|tx| {
-            //     println!("transactions: {:?}", tx);
-            // });
-        }
+    let filters = Filters {
+        whitelisted_packages,
+        dispatcher: hokko::dispatcher(&app_state.config.collections.tracked),
+    };
 
-        // tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
-    }
+    let shutdown = Shutdown::on_signals();
+    indexer::pipeline::run(app_state, Arc::new(filters), pipeline, shutdown).await
 }
 
-async fn get_checkpoints(
-    client: Arc<SuiClient>,
-    next_cursor: &mut BigInt<u64>,
-) -> Result<Vec<Checkpoint>, anyhow::Error> {
-    let response = client
-        .read_api()
-        .get_checkpoints(Some(*next_cursor), Some(LIMIT), false)
-        .await?;
-
-    *next_cursor = response.next_cursor.unwrap();
+/// Picks where chain data comes from:
+/// - `RPC_REPLAY_DIR` serves responses captured by a previous recording run
+/// - `LOCAL_CHECKPOINTS_DIR` reads a directory of recorded JSON responses
+/// - otherwise the configured JSON-RPC endpoints, recording every response to
+///   `RPC_RECORD_DIR` when set
+async fn build_source(config: &Config) -> Result<Arc<dyn CheckpointSource>, anyhow::Error> {
+    if let Ok(dir) = std::env::var("RPC_REPLAY_DIR") {
+        println!("Replaying RPC responses from {dir}");
+        return Ok(Arc::new(ReplayCheckpointSource::new(dir)));
+    }
 
-    Ok(response.data)
-}
+    if let Ok(dir) = std::env::var("LOCAL_CHECKPOINTS_DIR") {
+        println!("Reading checkpoints from {dir}");
+        return Ok(Arc::new(FileCheckpointSource::new(dir)));
+    }
 
-fn partition_transactions_by_limit_of(
-    transactions: &Vec<TransactionDigest>,
-    limit: usize,
-) -> Vec<Vec<TransactionDigest>> {
-    transactions
-        .chunks(limit)
-        .map(|chunk| chunk.to_vec())
-        .collect()
+    let rpc = RpcCheckpointSource::connect(config).await?;
+    match std::env::var("RPC_RECORD_DIR") {
+        Ok(dir) => {
+            println!("Recording RPC responses to {dir}");
+            Ok(Arc::new(RecordingCheckpointSource::new(rpc, dir)))
+        }
+        Err(_) => Ok(Arc::new(rpc)),
+    }
 }
@@ -1,54 +1,56 @@
+use anyhow::Error;
 use sui_sdk::rpc_types::SuiObjectResponse;
 use sui_sdk::types::object::Owner;
 
 use crate::AppState;
 use crate::processor::model::{NftData, OwnerType};
-use crate::store::store::store_nft;
+use crate::store::kiosk::find_kiosk_owner;
 
-pub async fn process_object(app_state: crate::AppState, objects: Vec<SuiObjectResponse>) {
-    objects
+/// Builds `NftData` for every displayable object, resolving who holds it.
+pub async fn assemble_objects(
+    app_state: &AppState,
+    objects: Vec<SuiObjectResponse>,
+) -> Result<Vec<NftData>, Error> {
+    let objects = objects
         .into_iter()
-        .filter(|obj| {
-            obj.data
-                .as_ref()
-                .map_or(false, |data| data.display.is_some())
-        })
-        .for_each(|obj| {
-            let object = obj.data.unwrap(); // Safe to unwrap
-            let owner = object.owner.clone();
-            if let Some(mut nft) = NftData::from_object_data(&object) {
-                let app_state = app_state.clone();
-                let owner = tokio::runtime::Handle::current().block_on(extract_ownership(
-                    &app_state,
-                    owner.as_ref(),
-                    &object.object_id.to_string(),
-                ));
+        .filter_map(|obj| obj.data)
+        .filter(|data| data.display.is_some());
 
-                nft.item_holder = Some(owner);
-                println!("Assembled NFT: {nft:#?}");
-                tokio::task::spawn(async move {
-                    // Send nft for storing into the database
-                    store_nft(app_state, nft).await;
-                });
-            }
-        });
+    let mut nfts = Vec::new();
+    for object in objects {
+        let Some(mut nft) = NftData::from_object_data(&object) else {
+            continue;
+        };
+        let owner = extract_ownership(
+            app_state,
+            object.owner.as_ref(),
+            &object.object_id.to_string(),
+        )
+        .await?;
+
+        nft.item_holder = Some(owner);
+        println!("Assembled NFT: {nft:#?}");
+        nfts.push(nft);
+    }
+
+    Ok(nfts)
 }
 
 async fn extract_ownership(
     app_state: &crate::AppState,
     owner: Option<&Owner>,
     object_id: &str,
-) -> OwnerType {
+) -> Result<OwnerType, Error> {
     // Handle null/None owners
     let Some(owner) = owner else {
         eprintln!("Invalid owner type for {}: None", object_id);
-        return OwnerType {
+        return Ok(OwnerType {
             address_owner: Some("error".to_string()),
             object_owner: Some("error".to_string()),
-        };
+        });
     };
 
-    match owner {
+    let owner = match owner {
         // Handle shared owners
         Owner::Shared { .. } => OwnerType {
             address_owner: Some("Shared".to_string()),
@@ -65,25 +67,49 @@ async fn extract_ownership(
             object_owner: None,
         },
         // Handle object owners (likely in kiosk)
-        Owner::ObjectOwner(object_id) => rpc_owner(app_state.clone(), object_id.to_string()).await,
+        Owner::ObjectOwner(wrapper_id) => {
+            resolve_object_owner(
+                wrapper_id.to_string(),
+                async |wrapper_id| rpc_owner(app_state.clone(), wrapper_id).await,
+                async |kiosk| Ok(find_kiosk_owner(app_state.db.as_ref(), kiosk).await?),
+            )
+            .await?
+        }
         // Handle consensus address owners
         Owner::ConsensusAddressOwner { owner, .. } => OwnerType {
             address_owner: Some(owner.to_string()),
             object_owner: None,
         },
-    }
+    };
+
+    Ok(owner)
 }
 
-async fn rpc_owner(app_state: AppState, object_owner_id: String) -> OwnerType {
-    match app_state
-        .client
-        .read_api()
-        .get_object_with_options(
-            object_owner_id.parse().expect("Valid ObjectID"),
-            sui_sdk::rpc_types::SuiObjectDataOptions::default()
-                .with_owner()
-                .with_type(),
-        )
+/// Kiosk items are owned by the dynamic field wrapping them, which the kiosk owns in turn.
+/// `owner_of` looks up the wrapper's owner and `kiosk_owner` maps that kiosk to its wallet.
+async fn resolve_object_owner(
+    wrapper_id: String,
+    owner_of: impl AsyncFnOnce(String) -> Result<OwnerType, Error>,
+    kiosk_owner: impl AsyncFnOnce(&str) -> Result<Option<String>, Error>,
+) -> Result<OwnerType, Error> {
+    let owner = owner_of(wrapper_id).await?;
+    let Some(kiosk) = owner.object_owner.clone() else {
+        return Ok(owner);
+    };
+
+    Ok(match kiosk_owner(&kiosk).await? {
+        Some(wallet) => OwnerType {
+            address_owner: Some(wallet),
+            object_owner: Some(kiosk),
+        },
+        None => owner,
+    })
+}
+
+async fn rpc_owner(app_state: AppState, object_owner_id: String) -> Result<OwnerType, Error> {
+    let owner = match app_state
+        .source
+        .get_object(object_owner_id.parse().expect("Valid ObjectID"))
         .await
     {
         Ok(object_response) => {
@@ -129,12 +155,34 @@ async fn rpc_owner(app_state: AppState, object_owner_id: String) -> OwnerType {
                 }
             }
         }
-        Err(error) => {
-            eprintln!("Error fetching owner: {}", error);
-            OwnerType {
-                address_owner: Some("error".to_string()),
-                object_owner: Some(object_owner_id),
-            }
-        }
+        // Transient failures were already retried by the source
+        Err(error) => return Err(error.context(format!("Fetching owner {object_owner_id}"))),
+    };
+
+    Ok(owner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_kiosk_items_resolve_through_their_field() {
+        let owner = resolve_object_owner(
+            "0xfield".to_string(),
+            async |wrapper_id| {
+                assert_eq!(wrapper_id, "0xfield");
+                Ok(OwnerType {
+                    address_owner: None,
+                    object_owner: Some("0xkiosk".to_string()),
+                })
+            },
+            async |kiosk| Ok((kiosk == "0xkiosk").then(|| "0xwallet".to_string())),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(owner.address_owner.as_deref(), Some("0xwallet"));
+        assert_eq!(owner.object_owner.as_deref(), Some("0xkiosk"));
     }
 }
@@ -2,140 +2,122 @@ use std::collections::HashSet;
 use std::str::FromStr;
 
 use anyhow::Error;
+use futures::StreamExt;
 use sui_sdk::{
-    rpc_types::{
-        ObjectChange, SuiEvent, SuiObjectDataOptions, SuiObjectResponse,
-        SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
-    },
-    types::{base_types::ObjectID, digests::TransactionDigest},
+    rpc_types::{ObjectChange, SuiEvent, SuiObjectResponse, SuiTransactionBlockResponse},
+    types::base_types::ObjectID,
 };
 
+use crate::hokko::dispatcher::{Dispatcher, EventContext};
+
+/// Runs the Hokko handlers for the events of every transaction, in emission order.
+/// `timestamp_ms` is the timestamp of the checkpoint holding `transactions`.
+pub async fn dispatch_events(
+    app_state: &crate::AppState,
+    transactions: &[SuiTransactionBlockResponse],
+    timestamp_ms: u64,
+    dispatcher: &Dispatcher,
+) -> Result<(), Error> {
+    for events in transactions
+        .iter()
+        .filter_map(|block| block.events.as_ref())
+    {
+        let hokko_transaction = events
+            .data
+            .iter()
+            .any(|event| dispatcher.is_hokko_package(&event.package_id.to_string()));
+
+        for event in &events.data {
+            let context = EventContext {
+                event: event.clone(),
+                digest: event.id.tx_digest.to_string(),
+                sender: event.sender.to_string(),
+                timestamp_ms,
+                hokko_transaction,
+            };
+            dispatcher.dispatch(app_state, context).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Collects the objects referenced by NFT events and the tracked NFTs changed by each
+/// transaction.
 pub async fn find_potential_nfts(
     app_state: crate::AppState,
-    digests: Vec<TransactionDigest>,
-    option_transaction: SuiTransactionBlockResponseOptions,
-    option_object: SuiObjectDataOptions,
+    objects: Vec<SuiTransactionBlockResponse>,
     whitelisted_packages: &HashSet<String>,
-    hokko_packages: &HashSet<String>,
 ) -> Result<Vec<SuiObjectResponse>, Error> {
-    // let digests = checkpoint.transactions;
-    let objects: Vec<SuiTransactionBlockResponse> = app_state
-        .client
-        .read_api()
-        .multi_get_transactions_with_options(digests, option_transaction)
-        .await?;
-
-    let mut tasks = Vec::new();
+    let mut object_id_chunks: Vec<Vec<ObjectID>> = Vec::new();
 
     for block in &objects {
-        if let Some(block_event) = &block.events {
-            let vec_event = block_event.data.iter();
-            let mut seen_ids = HashSet::new();
-            let mut object_ids: Vec<ObjectID> = Vec::new();
-
-            vec_event.for_each(|event| {
-                if hokko_packages.contains(&event.package_id.to_string()) {
-                    // TODO: Create a Hokko module to handle the logic of processing these events
-                    tokio::task::spawn(async move { todo!() });
-                };
+        let mut seen_ids = HashSet::new();
+        let mut object_ids: Vec<ObjectID> = Vec::new();
 
-                let nft_id = extract_id(event);
-                if let Some(id) = nft_id {
+        if let Some(block_event) = &block.events {
+            for event in &block_event.data {
+                if let Some(id) = extract_id(event) {
                     if seen_ids.insert(id) {
                         object_ids.push(id);
                     }
                 }
-            });
-
-            let id_len = object_ids.len();
-            if id_len <= 50 && id_len != 0 {
-                let client = app_state.client.clone();
-                let options_object = option_object.clone();
-                let task = tokio::task::spawn(async move {
-                    client
-                        .read_api()
-                        .multi_get_object_with_options(object_ids, options_object)
-                        .await
-                });
-                tasks.push(task);
-            } else if id_len > 50 {
-                todo!();
             }
         }
 
-        if let Some(objects) = &block.object_changes {
-            // objects[0].to_owned
-            // println!("Changed objects: {objects:#?}");
-
-            objects.iter().for_each(|obj| {
-                // let object_id = obj.object_ref().0;
-                //TODO: if object_id is in the blacklist skip this object
-                match obj {
-                    ObjectChange::Mutated {
-                        // sender,
-                        // owner,
-                        // object_id,
-                        object_type,
-                        // digest,
-                        ..
-                    } => {
-                        let whole_type = format!(
-                            "{}::{}::{}",
-                            object_type.address, object_type.module, object_type.name
-                        );
-                        if whitelisted_packages.contains(&whole_type) {
-                            tokio::task::spawn(async move {
-                                //TODO: Add logic when object has been mutaded
-                                todo!()
-                            });
-                        }
-                    }
-                    ObjectChange::Transferred {
-                        // sender,
-                        // recipient,
-                        // object_id,
-                        object_type,
-                        // digest,
-                        ..
-                    } => {
-                        let whole_type = format!(
-                            "{}::{}::{}",
-                            object_type.address, object_type.module, object_type.name
-                        );
-                        if whitelisted_packages.contains(&whole_type) {
-                            tokio::task::spawn(async move {
-                                //TODO: Add logic when object has been transfered
-                                todo!()
-                            });
-                        }
-                    }
-                    _ => {}
+        // Tracked NFTs that changed hands or were mutated without an NFT event are fetched
+        // again so the assemble stage picks up their new holder and display data
+        if let Some(changes) = &block.object_changes {
+            for change in changes {
+                let (ObjectChange::Mutated {
+                    object_id,
+                    object_type,
+                    ..
+                }
+                | ObjectChange::Transferred {
+                    object_id,
+                    object_type,
+                    ..
+                }) = change
+                else {
+                    continue;
+                };
+                let whole_type = format!(
+                    "{}::{}::{}",
+                    object_type.address, object_type.module, object_type.name
+                );
+                if whitelisted_packages.contains(&whole_type) && seen_ids.insert(*object_id) {
+                    object_ids.push(*object_id);
                 }
-            });
+            }
         }
+
+        // Bulk mints and batch transfers can reference more IDs than one RPC call accepts
+        object_id_chunks.extend(
+            object_ids
+                .chunks(app_state.config.batch.objects)
+                .map(|chunk| chunk.to_vec()),
+        );
     }
 
+    let results: Vec<_> = futures::stream::iter(object_id_chunks)
+        .map(|object_ids| {
+            let source = app_state.source.clone();
+            async move { source.multi_get_objects(object_ids).await }
+        })
+        .buffered(app_state.config.concurrency.object_queries)
+        .collect()
+        .await;
+
+    // Retries already happened in the source, so a failure here must stop the checkpoint
     let mut potential_nfts = Vec::new();
-    for task in tasks {
-        let result = task.await?;
-        if let Ok(objects) = result {
-            potential_nfts.extend(objects);
-        }
+    for result in results {
+        potential_nfts.extend(result?);
     }
 
     Ok(potential_nfts)
 }
 
-// fn partition_transactions_by_limit_of(
-//     transactions: &Vec<TransactionDigest>,
-//     limit: usize,
-// ) -> Vec<Vec<TransactionDigest>> {
-//     transactions
-//         .chunks(limit)
-//         .map(|chunk| chunk.to_vec())
-//         .collect()
-// }
-
 fn extract_id(event: &SuiEvent) -> Option<ObjectID> {
     let name = event.type_.clone().to_string().to_lowercase();
     if name.contains("token") | name.contains("coin") {
@@ -0,0 +1,228 @@
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::{Error, anyhow, bail};
+use sui_sdk::{SuiClient, SuiClientBuilder};
+
+use crate::source::retry::{RetryPolicy, is_transient};
+
+// Consecutive failures after which an endpoint is taken out of rotation
+const FAILURE_THRESHOLD: u32 = 3;
+// How long an unhealthy endpoint is skipped before it is tried again
+const UNHEALTHY_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// Client-side token bucket: allows bursts of `capacity` requests and refills at
+/// `refill_per_second`.
+pub struct TokenBucket {
+    capacity: f64,
+    refill_per_second: f64,
+    state: Mutex<BucketState>,
+}
+
+struct BucketState {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    pub fn new(capacity: u32, refill_per_second: u32) -> Self {
+        Self {
+            capacity: capacity as f64,
+            refill_per_second: refill_per_second.max(1) as f64,
+            state: Mutex::new(BucketState {
+                tokens: capacity as f64,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Waits until a request may be sent.
+    pub async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut state = self.state.lock().unwrap();
+                let now = Instant::now();
+                let refilled =
+                    now.duration_since(state.last_refill).as_secs_f64() * self.refill_per_second;
+                state.tokens = (state.tokens + refilled).min(self.capacity);
+                state.last_refill = now;
+
+                if state.tokens >= 1.0 {
+                    state.tokens -= 1.0;
+                    return;
+                }
+                Duration::from_secs_f64((1.0 - state.tokens) / self.refill_per_second)
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[derive(Default)]
+struct Health {
+    consecutive_failures: u32,
+    unhealthy_until: Option<Instant>,
+}
+
+/// One RPC provider with its own rate limit and health record.
+pub struct Endpoint {
+    url: String,
+    client: SuiClient,
+    limiter: TokenBucket,
+    health: Mutex<Health>,
+}
+
+impl Endpoint {
+    pub fn new(url: String, client: SuiClient, requests_per_second: u32) -> Self {
+        Self {
+            url,
+            client,
+            limiter: TokenBucket::new(requests_per_second, requests_per_second),
+            health: Mutex::new(Health::default()),
+        }
+    }
+
+    fn is_healthy(&self, now: Instant) -> bool {
+        let health = self.health.lock().unwrap();
+        health.unhealthy_until.is_none_or(|until| now >= until)
+    }
+
+    fn record_success(&self) {
+        let mut health = self.health.lock().unwrap();
+        health.consecutive_failures = 0;
+        health.unhealthy_until = None;
+    }
+
+    fn record_failure(&self) {
+        let mut health = self.health.lock().unwrap();
+        health.consecutive_failures += 1;
+        if health.consecutive_failures >= FAILURE_THRESHOLD {
+            health.unhealthy_until = Some(Instant::now() + UNHEALTHY_COOLDOWN);
+            eprintln!(
+                "RPC endpoint {} marked unhealthy after {} failures",
+                self.url, health.consecutive_failures
+            );
+        }
+    }
+}
+
+/// Ordered list of RPC endpoints. Calls go to the first healthy endpoint and fail over
+/// to the next one on error; unhealthy endpoints are only used when nothing else is left.
+/// When every endpoint fails transiently the whole round is retried with backoff.
+pub struct EndpointPool {
+    endpoints: Vec<Endpoint>,
+    retry: RetryPolicy,
+}
+
+impl EndpointPool {
+    pub fn new(endpoints: Vec<Endpoint>, retry: RetryPolicy) -> Result<Self, Error> {
+        if endpoints.is_empty() {
+            bail!("At least one RPC endpoint is required");
+        }
+        Ok(Self { endpoints, retry })
+    }
+
+    /// Connects to every URL, skipping the ones that are unreachable at startup.
+    pub async fn connect(
+        urls: &[String],
+        requests_per_second: u32,
+        retry: RetryPolicy,
+    ) -> Result<Self, Error> {
+        let mut endpoints = Vec::with_capacity(urls.len());
+        for url in urls {
+            match SuiClientBuilder::default().build(url).await {
+                Ok(client) => {
+                    endpoints.push(Endpoint::new(url.clone(), client, requests_per_second))
+                }
+                Err(e) => eprintln!("Skipping RPC endpoint {url}: {e}"),
+            }
+        }
+        Self::new(endpoints)
+            .map_err(|_| anyhow!("None of the RPC endpoints {urls:?} are reachable"))
+    }
+
+    /// Healthy endpoints first, in configured order, followed by the unhealthy ones.
+    fn candidates(&self) -> impl Iterator<Item = &Endpoint> {
+        let now = Instant::now();
+        let healthy = self.endpoints.iter().filter(move |e| e.is_healthy(now));
+        let unhealthy = self.endpoints.iter().filter(move |e| !e.is_healthy(now));
+        healthy.chain(unhealthy)
+    }
+
+    /// Runs `request` against the pool, failing over between endpoints and retrying
+    /// transient failures. Permanent failures are returned straight away.
+    pub async fn call<T, F, Fut>(&self, request: F) -> Result<T, Error>
+    where
+        F: Fn(SuiClient) -> Fut,
+        Fut: Future<Output = Result<T, sui_sdk::error::Error>>,
+    {
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+
+            let mut last_error = None;
+            for endpoint in self.candidates() {
+                endpoint.limiter.acquire().await;
+                let response =
+                    tokio::time::timeout(self.retry.timeout, request(endpoint.client.clone()))
+                        .await;
+                match response {
+                    Ok(Ok(response)) => {
+                        endpoint.record_success();
+                        return Ok(response);
+                    }
+                    Ok(Err(e)) if !is_transient(&e) => {
+                        // The endpoint answered, so another endpoint or attempt would fail the same way
+                        return Err(Error::from(e)
+                            .context(format!("Permanent RPC failure from {}", endpoint.url)));
+                    }
+                    Ok(Err(e)) => {
+                        eprintln!("RPC call to {} failed: {e}", endpoint.url);
+                        endpoint.record_failure();
+                        last_error = Some(Error::from(e));
+                    }
+                    Err(_) => {
+                        eprintln!(
+                            "RPC call to {} timed out after {:?}",
+                            endpoint.url, self.retry.timeout
+                        );
+                        endpoint.record_failure();
+                        last_error = Some(anyhow!(
+                            "RPC call to {} timed out after {:?}",
+                            endpoint.url,
+                            self.retry.timeout
+                        ));
+                    }
+                }
+            }
+
+            let error = last_error.unwrap_or_else(|| anyhow!("No RPC endpoint available"));
+            if attempt >= self.retry.max_attempts {
+                return Err(error.context(format!("RPC call failed after {attempt} attempts")));
+            }
+
+            let backoff = self.retry.backoff(attempt);
+            eprintln!("Retrying RPC call in {backoff:?} (attempt {attempt}): {error}");
+            tokio::time::sleep(backoff).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_token_bucket_limits_rate_after_burst() {
+        let bucket = TokenBucket::new(2, 20);
+        let started = Instant::now();
+
+        // The burst is served immediately, the next two wait for refills (~50ms each)
+        for _ in 0..4 {
+            bucket.acquire().await;
+        }
+
+        assert!(started.elapsed() >= Duration::from_millis(90));
+    }
+}
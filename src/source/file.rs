@@ -0,0 +1,199 @@
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Error};
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use sui_sdk::{
+    rpc_types::{Checkpoint, SuiObjectResponse, SuiTransactionBlockResponse},
+    types::{base_types::ObjectID, digests::TransactionDigest},
+};
+
+use crate::source::CheckpointSource;
+
+/// Reads recorded JSON-RPC responses from a directory so the pipeline can run without a
+/// full node. The directory is laid out as:
+///
+/// ```text
+/// checkpoints/<sequence_number>.json   Checkpoint
+/// transactions/<digest>.json           SuiTransactionBlockResponse
+/// objects/<object_id>.json             SuiObjectResponse
+/// ```
+///
+/// The stream ends at the first missing checkpoint file.
+pub struct FileCheckpointSource {
+    root: PathBuf,
+}
+
+impl FileCheckpointSource {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path(&self, kind: &str, key: impl std::fmt::Display) -> PathBuf {
+        self.root.join(kind).join(format!("{key}.json"))
+    }
+}
+
+/// Returns `None` when the file does not exist.
+async fn read_json<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, Error> {
+    let contents = match tokio::fs::read_to_string(path).await {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("Reading {}", path.display())),
+    };
+    let value =
+        serde_json::from_str(&contents).with_context(|| format!("Decoding {}", path.display()))?;
+
+    Ok(Some(value))
+}
+
+#[async_trait]
+impl CheckpointSource for FileCheckpointSource {
+    /// The highest recorded checkpoint, or 0 for an empty recording.
+    async fn latest_checkpoint(&self) -> Result<u64, Error> {
+        let dir = self.root.join("checkpoints");
+        let mut entries = match tokio::fs::read_dir(&dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e).with_context(|| format!("Reading {}", dir.display())),
+        };
+
+        let mut latest = 0;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            let sequence_number = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<u64>().ok());
+            if let Some(sequence_number) = sequence_number {
+                latest = latest.max(sequence_number);
+            }
+        }
+
+        Ok(latest)
+    }
+
+    async fn get_checkpoints(&self, cursor: u64, limit: usize) -> Result<Vec<Checkpoint>, Error> {
+        let mut checkpoints = Vec::new();
+        for sequence_number in (cursor + 1..).take(limit) {
+            match read_json(&self.path("checkpoints", sequence_number)).await? {
+                Some(checkpoint) => checkpoints.push(checkpoint),
+                None => break,
+            }
+        }
+
+        Ok(checkpoints)
+    }
+
+    async fn multi_get_transactions(
+        &self,
+        digests: Vec<TransactionDigest>,
+    ) -> Result<Vec<SuiTransactionBlockResponse>, Error> {
+        let mut transactions = Vec::with_capacity(digests.len());
+        for digest in digests {
+            let path = self.path("transactions", digest);
+            let transaction = read_json(&path)
+                .await?
+                .with_context(|| format!("Missing recorded transaction {}", path.display()))?;
+            transactions.push(transaction);
+        }
+
+        Ok(transactions)
+    }
+
+    /// Objects without a recording are skipped, like objects the RPC no longer knows about.
+    async fn multi_get_objects(
+        &self,
+        object_ids: Vec<ObjectID>,
+    ) -> Result<Vec<SuiObjectResponse>, Error> {
+        let mut objects = Vec::with_capacity(object_ids.len());
+        for object_id in object_ids {
+            if let Some(object) = read_json(&self.path("objects", object_id)).await? {
+                objects.push(object);
+            }
+        }
+
+        Ok(objects)
+    }
+
+    async fn get_object(&self, object_id: ObjectID) -> Result<SuiObjectResponse, Error> {
+        let path = self.path("objects", object_id);
+        read_json(&path)
+            .await?
+            .with_context(|| format!("Missing recorded object {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, json};
+    use sui_sdk::types::digests::CheckpointDigest;
+
+    use super::*;
+
+    /// Aggregate signature of no signers: the compressed point at infinity.
+    const EMPTY_SIGNATURE: &str = "wAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA";
+
+    fn checkpoint(sequence_number: u64, transactions: &[TransactionDigest]) -> Value {
+        json!({
+            "epoch": "0",
+            "sequenceNumber": sequence_number.to_string(),
+            "digest": CheckpointDigest::new([sequence_number as u8; 32]).to_string(),
+            "networkTotalTransactions": transactions.len().to_string(),
+            "epochRollingGasCostSummary": {
+                "computationCost": "0",
+                "storageCost": "0",
+                "storageRebate": "0",
+                "nonRefundableStorageFee": "0"
+            },
+            "timestampMs": (1_700_000_000_000 + sequence_number).to_string(),
+            "transactions": transactions
+                .iter()
+                .map(|digest| digest.to_string())
+                .collect::<Vec<_>>(),
+            "checkpointCommitments": [],
+            "validatorSignature": EMPTY_SIGNATURE
+        })
+    }
+
+    async fn write_fixture(root: &Path, kind: &str, key: impl std::fmt::Display, value: Value) {
+        let dir = root.join(kind);
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        tokio::fs::write(dir.join(format!("{key}.json")), value.to_string())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reads_recorded_checkpoints() {
+        let dir = std::env::temp_dir().join(format!("rustdexer-file-{}", std::process::id()));
+        let digest = TransactionDigest::new([7; 32]);
+
+        write_fixture(&dir, "checkpoints", 1, checkpoint(1, &[digest])).await;
+        write_fixture(&dir, "checkpoints", 2, checkpoint(2, &[])).await;
+        let transaction = SuiTransactionBlockResponse::new(digest);
+        write_fixture(
+            &dir,
+            "transactions",
+            digest,
+            serde_json::to_value(&transaction).unwrap(),
+        )
+        .await;
+
+        let source = FileCheckpointSource::new(&dir);
+        assert_eq!(source.latest_checkpoint().await.unwrap(), 2);
+
+        let batch = source.next_checkpoints(0, 50).await.unwrap();
+        assert_eq!(batch.len(), 2);
+        assert_eq!(batch[0].checkpoint.sequence_number, 1);
+        assert_eq!(batch[0].transactions.len(), 1);
+        assert_eq!(batch[0].transactions[0].digest, digest);
+        assert!(batch[1].transactions.is_empty());
+
+        // The recording ends after checkpoint 2
+        assert!(source.next_checkpoints(2, 50).await.unwrap().is_empty());
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+}
@@ -0,0 +1,69 @@
+pub mod endpoint;
+pub mod file;
+pub mod replay;
+pub mod retry;
+pub mod rpc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use sui_sdk::{
+    rpc_types::{Checkpoint, SuiObjectResponse, SuiTransactionBlockResponse},
+    types::{base_types::ObjectID, digests::TransactionDigest},
+};
+
+/// A checkpoint together with the responses for every transaction it contains.
+#[derive(Debug, Clone)]
+pub struct CheckpointData {
+    pub checkpoint: Checkpoint,
+    pub transactions: Vec<SuiTransactionBlockResponse>,
+}
+
+/// Where the indexer reads chain data from.
+///
+/// Implementations only provide the raw reads; `next_checkpoints` combines them into
+/// the checkpoint batches consumed by the pipeline.
+#[async_trait]
+pub trait CheckpointSource: Send + Sync {
+    /// Sequence number of the newest checkpoint the source knows about.
+    async fn latest_checkpoint(&self) -> Result<u64, Error>;
+
+    /// Checkpoints after `cursor` in ascending order, at most `limit` of them.
+    async fn get_checkpoints(&self, cursor: u64, limit: usize) -> Result<Vec<Checkpoint>, Error>;
+
+    /// Transaction responses including events, effects and object changes, in `digests` order.
+    async fn multi_get_transactions(
+        &self,
+        digests: Vec<TransactionDigest>,
+    ) -> Result<Vec<SuiTransactionBlockResponse>, Error>;
+
+    /// Objects including content, type, display and owner.
+    async fn multi_get_objects(
+        &self,
+        object_ids: Vec<ObjectID>,
+    ) -> Result<Vec<SuiObjectResponse>, Error>;
+
+    /// A single object including at least its owner and type.
+    async fn get_object(&self, object_id: ObjectID) -> Result<SuiObjectResponse, Error>;
+
+    /// Checkpoints after `cursor` together with their transaction responses.
+    async fn next_checkpoints(
+        &self,
+        cursor: u64,
+        limit: usize,
+    ) -> Result<Vec<CheckpointData>, Error> {
+        let checkpoints = self.get_checkpoints(cursor, limit).await?;
+
+        let mut batch = Vec::with_capacity(checkpoints.len());
+        for checkpoint in checkpoints {
+            let transactions = self
+                .multi_get_transactions(checkpoint.transactions.clone())
+                .await?;
+            batch.push(CheckpointData {
+                checkpoint,
+                transactions,
+            });
+        }
+
+        Ok(batch)
+    }
+}
@@ -0,0 +1,293 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Error, bail};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+use sui_sdk::{
+    rpc_types::{Checkpoint, SuiObjectResponse, SuiTransactionBlockResponse},
+    types::{base_types::ObjectID, digests::TransactionDigest},
+};
+
+use crate::source::CheckpointSource;
+
+const GET_LATEST_CHECKPOINT: &str = "get_latest_checkpoint_sequence_number";
+const GET_CHECKPOINTS: &str = "get_checkpoints";
+const MULTI_GET_TRANSACTIONS: &str = "multi_get_transactions_with_options";
+const MULTI_GET_OBJECTS: &str = "multi_get_object_with_options";
+const GET_OBJECT: &str = "get_object_with_options";
+
+/// One recorded call, stored at `<dir>/<method>/<request hash>.json`.
+#[derive(Serialize, Deserialize)]
+struct Recording<T> {
+    request: Value,
+    response: T,
+}
+
+/// Stable across runs and toolchains (FNV-1a), unlike `std`'s `DefaultHasher`.
+fn request_key(request: &Value) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in request.to_string().bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{hash:016x}")
+}
+
+fn recording_path(dir: &Path, method: &str, request: &Value) -> PathBuf {
+    dir.join(method)
+        .join(format!("{}.json", request_key(request)))
+}
+
+/// Wraps another source and writes every response it returns to disk, so the exact
+/// traffic behind a production bug can later be served by `ReplayCheckpointSource`.
+pub struct RecordingCheckpointSource<S> {
+    inner: S,
+    dir: PathBuf,
+}
+
+impl<S> RecordingCheckpointSource<S> {
+    pub fn new(inner: S, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            inner,
+            dir: dir.into(),
+        }
+    }
+
+    async fn record<T: Serialize>(
+        &self,
+        method: &str,
+        request: Value,
+        response: &T,
+    ) -> Result<(), Error> {
+        let path = recording_path(&self.dir, method, &request);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let contents = serde_json::to_string_pretty(&Recording { request, response })?;
+        tokio::fs::write(&path, contents)
+            .await
+            .with_context(|| format!("Recording {method} to {}", path.display()))
+    }
+}
+
+#[async_trait]
+impl<S: CheckpointSource> CheckpointSource for RecordingCheckpointSource<S> {
+    async fn latest_checkpoint(&self) -> Result<u64, Error> {
+        // Only the most recent answer is kept, which is what a replay should report as the tip
+        let response = self.inner.latest_checkpoint().await?;
+        self.record(GET_LATEST_CHECKPOINT, json!({}), &response)
+            .await?;
+        Ok(response)
+    }
+
+    async fn get_checkpoints(&self, cursor: u64, limit: usize) -> Result<Vec<Checkpoint>, Error> {
+        let response = self.inner.get_checkpoints(cursor, limit).await?;
+        self.record(
+            GET_CHECKPOINTS,
+            json!({ "cursor": cursor, "limit": limit }),
+            &response,
+        )
+        .await?;
+        Ok(response)
+    }
+
+    async fn multi_get_transactions(
+        &self,
+        digests: Vec<TransactionDigest>,
+    ) -> Result<Vec<SuiTransactionBlockResponse>, Error> {
+        let request = json!({ "digests": &digests });
+        let response = self.inner.multi_get_transactions(digests).await?;
+        self.record(MULTI_GET_TRANSACTIONS, request, &response)
+            .await?;
+        Ok(response)
+    }
+
+    async fn multi_get_objects(
+        &self,
+        object_ids: Vec<ObjectID>,
+    ) -> Result<Vec<SuiObjectResponse>, Error> {
+        let request = json!({ "object_ids": &object_ids });
+        let response = self.inner.multi_get_objects(object_ids).await?;
+        self.record(MULTI_GET_OBJECTS, request, &response).await?;
+        Ok(response)
+    }
+
+    async fn get_object(&self, object_id: ObjectID) -> Result<SuiObjectResponse, Error> {
+        let response = self.inner.get_object(object_id).await?;
+        self.record(GET_OBJECT, json!({ "object_id": object_id }), &response)
+            .await?;
+        Ok(response)
+    }
+}
+
+/// Serves responses written by `RecordingCheckpointSource`. Any request that was not
+/// recorded is an error, except `get_checkpoints` past the end of the recording which
+/// returns no checkpoints.
+pub struct ReplayCheckpointSource {
+    dir: PathBuf,
+}
+
+impl ReplayCheckpointSource {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    async fn replay<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        request: Value,
+    ) -> Result<Option<T>, Error> {
+        let path = recording_path(&self.dir, method, &request);
+        let contents = match tokio::fs::read_to_string(&path).await {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("Reading {}", path.display())),
+        };
+        let recording: Recording<T> = serde_json::from_str(&contents)
+            .with_context(|| format!("Decoding {}", path.display()))?;
+        if recording.request != request {
+            bail!(
+                "Recording {} was made for {} but {request} was requested",
+                path.display(),
+                recording.request
+            );
+        }
+
+        Ok(Some(recording.response))
+    }
+
+    async fn replay_required<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        request: Value,
+    ) -> Result<T, Error> {
+        self.replay(method, request.clone())
+            .await?
+            .with_context(|| format!("No recorded {method} response for {request}"))
+    }
+}
+
+#[async_trait]
+impl CheckpointSource for ReplayCheckpointSource {
+    async fn latest_checkpoint(&self) -> Result<u64, Error> {
+        self.replay_required(GET_LATEST_CHECKPOINT, json!({})).await
+    }
+
+    async fn get_checkpoints(&self, cursor: u64, limit: usize) -> Result<Vec<Checkpoint>, Error> {
+        let request = json!({ "cursor": cursor, "limit": limit });
+        Ok(self
+            .replay(GET_CHECKPOINTS, request)
+            .await?
+            .unwrap_or_default())
+    }
+
+    async fn multi_get_transactions(
+        &self,
+        digests: Vec<TransactionDigest>,
+    ) -> Result<Vec<SuiTransactionBlockResponse>, Error> {
+        self.replay_required(MULTI_GET_TRANSACTIONS, json!({ "digests": digests }))
+            .await
+    }
+
+    async fn multi_get_objects(
+        &self,
+        object_ids: Vec<ObjectID>,
+    ) -> Result<Vec<SuiObjectResponse>, Error> {
+        self.replay_required(MULTI_GET_OBJECTS, json!({ "object_ids": object_ids }))
+            .await
+    }
+
+    async fn get_object(&self, object_id: ObjectID) -> Result<SuiObjectResponse, Error> {
+        self.replay_required(GET_OBJECT, json!({ "object_id": object_id }))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use sui_sdk::rpc_types::SuiObjectResponseError;
+
+    use super::*;
+
+    /// Answers every object request with `NotExists` and never has checkpoints.
+    struct EmptySource;
+
+    #[async_trait]
+    impl CheckpointSource for EmptySource {
+        async fn latest_checkpoint(&self) -> Result<u64, Error> {
+            Ok(0)
+        }
+
+        async fn get_checkpoints(&self, _: u64, _: usize) -> Result<Vec<Checkpoint>, Error> {
+            Ok(Vec::new())
+        }
+
+        async fn multi_get_transactions(
+            &self,
+            _: Vec<TransactionDigest>,
+        ) -> Result<Vec<SuiTransactionBlockResponse>, Error> {
+            Ok(Vec::new())
+        }
+
+        async fn multi_get_objects(
+            &self,
+            object_ids: Vec<ObjectID>,
+        ) -> Result<Vec<SuiObjectResponse>, Error> {
+            Ok(object_ids
+                .into_iter()
+                .map(|object_id| {
+                    SuiObjectResponse::new_with_error(SuiObjectResponseError::NotExists {
+                        object_id,
+                    })
+                })
+                .collect())
+        }
+
+        async fn get_object(&self, object_id: ObjectID) -> Result<SuiObjectResponse, Error> {
+            Ok(SuiObjectResponse::new_with_error(
+                SuiObjectResponseError::NotExists { object_id },
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replay_serves_recorded_responses() {
+        let dir = std::env::temp_dir().join(format!("rustdexer-replay-{}", std::process::id()));
+        let object_id = ObjectID::from_str(
+            "0x9b58b1e31a8adfd6c1fc29111f6b308d0fe4a08bd1bac36234a601697d8a9e57",
+        )
+        .unwrap();
+
+        let recorder = RecordingCheckpointSource::new(EmptySource, &dir);
+        let recorded = recorder.multi_get_objects(vec![object_id]).await.unwrap();
+        recorder.get_object(object_id).await.unwrap();
+
+        let replay = ReplayCheckpointSource::new(&dir);
+        let replayed = replay.multi_get_objects(vec![object_id]).await.unwrap();
+        assert_eq!(
+            serde_json::to_value(&recorded).unwrap(),
+            serde_json::to_value(&replayed).unwrap()
+        );
+        assert!(replay.get_object(object_id).await.is_ok());
+
+        // Anything outside the recording is reported instead of silently guessed
+        assert!(replay.multi_get_objects(Vec::new()).await.is_err());
+        assert!(replay.get_checkpoints(1, 50).await.unwrap().is_empty());
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[test]
+    fn test_request_key_is_stable() {
+        // FNV-1a of `{"cursor":193710192,"limit":50}`: recordings already on disk depend on it
+        let request = json!({ "cursor": 193710192u64, "limit": 50 });
+        assert_eq!(request_key(&request), "6e9ccaa4afa6bd72");
+        assert_ne!(
+            request_key(&request),
+            request_key(&json!({ "cursor": 193710193u64, "limit": 50 }))
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use jsonrpsee_core::ClientError;
+use jsonrpsee_http_client::transport::Error as HttpTransportError;
+
+/// How RPC calls are retried when every endpoint fails with a transient error.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total attempts including the first one.
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Upper bound for a single request to a single endpoint.
+    pub timeout: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(10),
+            timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `attempt` (starting at 1): exponential growth capped at
+    /// `max_backoff`, with the upper half randomised so callers do not retry in lockstep.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponential = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)));
+        let capped = exponential.min(self.max_backoff);
+        let half = capped / 2;
+
+        half + half.mul_f64(jitter())
+    }
+}
+
+/// A value in `[0, 1)` without pulling in a random number generator crate.
+fn jitter() -> f64 {
+    let random = RandomState::new().build_hasher().finish();
+    (random >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Whether retrying `error` can succeed. Connection failures, timeouts, rate limiting and
+/// server errors are transient. Errors returned by the node for the call itself (invalid
+/// params, missing objects) and errors decoding or validating a response are permanent.
+pub fn is_transient(error: &sui_sdk::error::Error) -> bool {
+    match error {
+        sui_sdk::error::Error::RpcError(error) => is_transient_rpc(error),
+        _ => false,
+    }
+}
+
+fn is_transient_rpc(error: &ClientError) -> bool {
+    match error {
+        ClientError::Transport(error) => match error.downcast_ref::<HttpTransportError>() {
+            Some(HttpTransportError::Rejected { status_code }) => is_transient_status(*status_code),
+            _ => true,
+        },
+        ClientError::RequestTimeout | ClientError::RestartNeeded(_) => true,
+        _ => false,
+    }
+}
+
+fn is_transient_status(status_code: u16) -> bool {
+    status_code == 429 || (500..600).contains(&status_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_grows_and_is_capped() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(1),
+            timeout: Duration::from_secs(1),
+        };
+
+        for attempt in 1..=10 {
+            let expected =
+                (Duration::from_millis(100) * 2u32.pow(attempt - 1)).min(Duration::from_secs(1));
+            let backoff = policy.backoff(attempt);
+            assert!(backoff >= expected / 2, "attempt {attempt}: {backoff:?}");
+            assert!(backoff <= expected, "attempt {attempt}: {backoff:?}");
+        }
+    }
+
+    #[test]
+    fn test_data_errors_are_permanent() {
+        let error = sui_sdk::error::Error::DataError("unexpected response".to_string());
+        assert!(!is_transient(&error));
+    }
+
+    #[test]
+    fn test_call_errors_are_permanent() {
+        let error = ClientError::Call(jsonrpsee_types::ErrorObject::owned(
+            -32602,
+            "Invalid params",
+            None::<()>,
+        ));
+        assert!(!is_transient(&sui_sdk::error::Error::RpcError(error)));
+    }
+
+    #[test]
+    fn test_rejected_requests_are_transient_only_for_throttling_and_server_errors() {
+        let rejected = |status_code| {
+            sui_sdk::error::Error::RpcError(ClientError::Transport(Box::new(
+                HttpTransportError::Rejected { status_code },
+            )))
+        };
+
+        assert!(is_transient(&rejected(429)));
+        assert!(is_transient(&rejected(503)));
+        assert!(!is_transient(&rejected(400)));
+        assert!(!is_transient(&rejected(404)));
+        assert!(is_transient(&sui_sdk::error::Error::RpcError(
+            ClientError::RequestTimeout
+        )));
+    }
+}
@@ -0,0 +1,184 @@
+use std::sync::Arc;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use sui_sdk::{
+    rpc_types::{
+        Checkpoint, SuiObjectDataOptions, SuiObjectResponse, SuiTransactionBlockResponse,
+        SuiTransactionBlockResponseOptions,
+    },
+    types::{base_types::ObjectID, digests::TransactionDigest, sui_serde::BigInt},
+};
+
+use crate::config::settings::Config;
+use crate::source::{CheckpointSource, endpoint::EndpointPool};
+
+/// Reads checkpoints from Sui full nodes over JSON-RPC. Every call goes through the
+/// endpoint pool, so it is rate limited, retried and fails over between providers.
+pub struct RpcCheckpointSource {
+    pool: Arc<EndpointPool>,
+    /// Digests per multi_get_transactions_with_options call.
+    transactions_per_request: usize,
+    option_transaction: SuiTransactionBlockResponseOptions,
+    option_object: SuiObjectDataOptions,
+}
+
+impl RpcCheckpointSource {
+    pub async fn connect(config: &Config) -> Result<Self, Error> {
+        let pool = EndpointPool::connect(
+            &config.rpc_endpoints(),
+            config.rpc.requests_per_second,
+            config.retry_policy(),
+        )
+        .await?;
+        Ok(Self::new(pool, config.batch.transactions))
+    }
+
+    pub fn new(pool: EndpointPool, transactions_per_request: usize) -> Self {
+        let option_transaction = SuiTransactionBlockResponseOptions::new()
+            .with_events()
+            .with_effects()
+            .with_object_changes();
+
+        let option_object = SuiObjectDataOptions::new()
+            .with_content()
+            .with_type()
+            .with_display()
+            .with_owner();
+
+        Self {
+            pool: Arc::new(pool),
+            transactions_per_request,
+            option_transaction,
+            option_object,
+        }
+    }
+}
+
+#[async_trait]
+impl CheckpointSource for RpcCheckpointSource {
+    async fn latest_checkpoint(&self) -> Result<u64, Error> {
+        self.pool
+            .call(|client| async move {
+                client
+                    .read_api()
+                    .get_latest_checkpoint_sequence_number()
+                    .await
+            })
+            .await
+    }
+
+    async fn get_checkpoints(&self, cursor: u64, limit: usize) -> Result<Vec<Checkpoint>, Error> {
+        // The cursor is exclusive: the response starts at the checkpoint after it
+        let response = self
+            .pool
+            .call(|client| async move {
+                client
+                    .read_api()
+                    .get_checkpoints(Some(BigInt::from(cursor)), Some(limit), false)
+                    .await
+            })
+            .await?;
+
+        Ok(response.data)
+    }
+
+    /// Checkpoints above the RPC limit are split into batches that are requested
+    /// concurrently and merged back in their original order.
+    async fn multi_get_transactions(
+        &self,
+        digests: Vec<TransactionDigest>,
+    ) -> Result<Vec<SuiTransactionBlockResponse>, Error> {
+        let partitions =
+            partition_transactions_by_limit_of(&digests, self.transactions_per_request);
+        let tasks: Vec<_> = partitions
+            .into_iter()
+            .map(|vec_tx| {
+                let options = self.option_transaction.clone();
+                let pool = self.pool.clone();
+
+                tokio::task::spawn(async move {
+                    pool.call(|client| {
+                        let vec_tx = vec_tx.clone();
+                        let options = options.clone();
+                        async move {
+                            client
+                                .read_api()
+                                .multi_get_transactions_with_options(vec_tx, options)
+                                .await
+                        }
+                    })
+                    .await
+                })
+            })
+            .collect();
+
+        let results = futures::future::try_join_all(tasks).await?;
+
+        let mut transactions = Vec::with_capacity(digests.len());
+        for result in results {
+            transactions.extend(result?);
+        }
+
+        Ok(transactions)
+    }
+
+    async fn multi_get_objects(
+        &self,
+        object_ids: Vec<ObjectID>,
+    ) -> Result<Vec<SuiObjectResponse>, Error> {
+        self.pool
+            .call(|client| {
+                let object_ids = object_ids.clone();
+                let options = self.option_object.clone();
+                async move {
+                    client
+                        .read_api()
+                        .multi_get_object_with_options(object_ids, options)
+                        .await
+                }
+            })
+            .await
+    }
+
+    async fn get_object(&self, object_id: ObjectID) -> Result<SuiObjectResponse, Error> {
+        self.pool
+            .call(|client| async move {
+                client
+                    .read_api()
+                    .get_object_with_options(
+                        object_id,
+                        SuiObjectDataOptions::default().with_owner().with_type(),
+                    )
+                    .await
+            })
+            .await
+    }
+}
+
+fn partition_transactions_by_limit_of(
+    transactions: &[TransactionDigest],
+    limit: usize,
+) -> Vec<Vec<TransactionDigest>> {
+    transactions
+        .chunks(limit)
+        .map(|chunk| chunk.to_vec())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partition_transactions_by_limit_of() {
+        let digests: Vec<TransactionDigest> =
+            (0..120).map(|_| TransactionDigest::random()).collect();
+
+        let partitions = partition_transactions_by_limit_of(&digests, 50);
+        assert_eq!(partitions.len(), 3);
+        assert_eq!(partitions[0].len(), 50);
+        assert_eq!(partitions[2].len(), 20);
+        assert_eq!(partitions.concat(), digests);
+    }
+}
@@ -0,0 +1,53 @@
+use chrono::NaiveDateTime;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter};
+
+use crate::entity::collection_offer;
+use crate::store::collection_stats::{collection_offer_added, collection_offer_removed};
+use crate::store::store::{ensure_collection, ensure_wallet};
+
+pub async fn insert_collection_offer<C: ConnectionTrait>(
+    db: &C,
+    offer: collection_offer::Model,
+) -> Result<(), DbErr> {
+    ensure_wallet(db, &offer.owner_wallet_address).await?;
+    ensure_collection(db, &offer.collection_type).await?;
+
+    let (collection_type, price, offered_at) =
+        (offer.collection_type.clone(), offer.price, offer.created_at);
+    let offer_entity: collection_offer::ActiveModel = offer.into();
+    let inserted = collection_offer::Entity::insert(offer_entity)
+        .on_conflict(
+            OnConflict::columns([
+                collection_offer::Column::CollectionType,
+                collection_offer::Column::OfferId,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+    if inserted > 0 {
+        collection_offer_added(db, &collection_type, price, offered_at).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn remove_collection_offer<C: ConnectionTrait>(
+    db: &C,
+    collection_type: &str,
+    offer_id: &str,
+    removed_at: NaiveDateTime,
+) -> Result<(), DbErr> {
+    let removed = collection_offer::Entity::delete_many()
+        .filter(collection_offer::Column::CollectionType.eq(collection_type))
+        .filter(collection_offer::Column::OfferId.eq(offer_id))
+        .exec_with_returning(db)
+        .await?;
+    for offer in removed {
+        collection_offer_removed(db, &offer.collection_type, offer.price, removed_at).await?;
+    }
+
+    Ok(())
+}
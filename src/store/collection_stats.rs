@@ -0,0 +1,333 @@
+//! Per-collection marketplace figures, updated as listings, collection offers and sales
+//! change instead of being aggregated over `Listing` on every read. The volume windows end
+//! at the current hour and are also re-summed periodically by the pipeline.
+
+use chrono::{Duration, NaiveDateTime, Timelike, Utc};
+use sea_orm::prelude::Expr;
+use sea_orm::sea_query::{OnConflict, SimpleExpr};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+};
+
+use crate::entity::{collection_offer, collection_stats, collection_volume, listing};
+
+pub async fn listing_added<C: ConnectionTrait>(
+    db: &C,
+    collection_type: &str,
+    price: i64,
+    at: NaiveDateTime,
+) -> Result<(), DbErr> {
+    ensure_stats(db, collection_type, at).await?;
+
+    collection_stats::Entity::update_many()
+        .col_expr(
+            collection_stats::Column::ListedCount,
+            Expr::col(collection_stats::Column::ListedCount).add(1),
+        )
+        // LEAST skips NULL, so the first listing sets the floor
+        .col_expr(
+            collection_stats::Column::FloorPrice,
+            Expr::cust_with_values("LEAST(floor_price, $1)", [price]),
+        )
+        .filter(collection_stats::Column::CollectionType.eq(collection_type))
+        .exec(db)
+        .await?;
+
+    refresh(db, collection_type, at).await
+}
+
+/// Must run after the listing row is gone, since a removed floor is looked up again.
+pub async fn listing_removed<C: ConnectionTrait>(
+    db: &C,
+    collection_type: &str,
+    price: i64,
+    at: NaiveDateTime,
+) -> Result<(), DbErr> {
+    ensure_stats(db, collection_type, at).await?;
+
+    collection_stats::Entity::update_many()
+        .col_expr(
+            collection_stats::Column::ListedCount,
+            Expr::col(collection_stats::Column::ListedCount).sub(1),
+        )
+        .filter(collection_stats::Column::CollectionType.eq(collection_type))
+        .exec(db)
+        .await?;
+    if floor_price(db, collection_type).await? == Some(price) {
+        recompute_floor(db, collection_type).await?;
+    }
+
+    refresh(db, collection_type, at).await
+}
+
+/// Must run after the listing row holds `new_price`.
+pub async fn listing_repriced<C: ConnectionTrait>(
+    db: &C,
+    collection_type: &str,
+    old_price: i64,
+    new_price: i64,
+    at: NaiveDateTime,
+) -> Result<(), DbErr> {
+    ensure_stats(db, collection_type, at).await?;
+
+    if new_price <= old_price {
+        collection_stats::Entity::update_many()
+            .col_expr(
+                collection_stats::Column::FloorPrice,
+                Expr::cust_with_values("LEAST(floor_price, $1)", [new_price]),
+            )
+            .filter(collection_stats::Column::CollectionType.eq(collection_type))
+            .exec(db)
+            .await?;
+    } else if floor_price(db, collection_type).await? == Some(old_price) {
+        recompute_floor(db, collection_type).await?;
+    }
+
+    refresh(db, collection_type, at).await
+}
+
+pub async fn collection_offer_added<C: ConnectionTrait>(
+    db: &C,
+    collection_type: &str,
+    price: i64,
+    at: NaiveDateTime,
+) -> Result<(), DbErr> {
+    ensure_stats(db, collection_type, at).await?;
+
+    collection_stats::Entity::update_many()
+        .col_expr(
+            collection_stats::Column::BestOffer,
+            Expr::cust_with_values("GREATEST(best_offer, $1)", [price]),
+        )
+        .filter(collection_stats::Column::CollectionType.eq(collection_type))
+        .exec(db)
+        .await?;
+
+    refresh(db, collection_type, at).await
+}
+
+/// Must run after the offer row is gone, since a removed best offer is looked up again.
+pub async fn collection_offer_removed<C: ConnectionTrait>(
+    db: &C,
+    collection_type: &str,
+    price: i64,
+    at: NaiveDateTime,
+) -> Result<(), DbErr> {
+    let stats = collection_stats::Entity::find_by_id(collection_type.to_string())
+        .one(db)
+        .await?;
+    if stats.is_some_and(|stats| stats.best_offer == Some(price)) {
+        let best_offer = collection_offer::Entity::find()
+            .select_only()
+            .column(collection_offer::Column::Price)
+            .filter(collection_offer::Column::CollectionType.eq(collection_type))
+            .order_by_desc(collection_offer::Column::Price)
+            .into_tuple::<i64>()
+            .one(db)
+            .await?;
+
+        collection_stats::Entity::update_many()
+            .col_expr(collection_stats::Column::BestOffer, Expr::value(best_offer))
+            .filter(collection_stats::Column::CollectionType.eq(collection_type))
+            .exec(db)
+            .await?;
+    }
+
+    refresh(db, collection_type, at).await
+}
+
+/// Adds a sale to the hourly volume buckets. Must run once per sale.
+pub async fn sale_recorded<C: ConnectionTrait>(
+    db: &C,
+    collection_type: &str,
+    price: i64,
+    at: NaiveDateTime,
+) -> Result<(), DbErr> {
+    ensure_stats(db, collection_type, at).await?;
+
+    let bucket = collection_volume::ActiveModel {
+        collection_type: Set(collection_type.to_string()),
+        hour: Set(hour_of(at)),
+        volume: Set(price),
+    };
+    collection_volume::Entity::insert(bucket)
+        .on_conflict(
+            OnConflict::columns([
+                collection_volume::Column::CollectionType,
+                collection_volume::Column::Hour,
+            ])
+            .value(
+                collection_volume::Column::Volume,
+                Expr::col((collection_volume::Entity, collection_volume::Column::Volume))
+                    .add(price),
+            )
+            .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+
+    refresh(db, collection_type, at).await
+}
+
+async fn ensure_stats<C: ConnectionTrait>(
+    db: &C,
+    collection_type: &str,
+    at: NaiveDateTime,
+) -> Result<(), DbErr> {
+    let stats_entity = collection_stats::ActiveModel {
+        collection_type: Set(collection_type.to_string()),
+        floor_price: Set(None),
+        listed_count: Set(0),
+        best_offer: Set(None),
+        volume_24h: Set(0),
+        volume_7d: Set(0),
+        updated_at: Set(at),
+    };
+
+    collection_stats::Entity::insert(stats_entity)
+        .on_conflict(
+            OnConflict::column(collection_stats::Column::CollectionType)
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+
+    Ok(())
+}
+
+async fn floor_price<C: ConnectionTrait>(
+    db: &C,
+    collection_type: &str,
+) -> Result<Option<i64>, DbErr> {
+    let stats = collection_stats::Entity::find_by_id(collection_type.to_string())
+        .one(db)
+        .await?;
+
+    Ok(stats.and_then(|stats| stats.floor_price))
+}
+
+/// Looks up the cheapest remaining listing, an index lookup on `(collectionType, price)`.
+async fn recompute_floor<C: ConnectionTrait>(db: &C, collection_type: &str) -> Result<(), DbErr> {
+    let floor = listing::Entity::find()
+        .select_only()
+        .column(listing::Column::Price)
+        .filter(listing::Column::CollectionType.eq(collection_type))
+        .order_by_asc(listing::Column::Price)
+        .into_tuple::<i64>()
+        .one(db)
+        .await?;
+
+    collection_stats::Entity::update_many()
+        .col_expr(collection_stats::Column::FloorPrice, Expr::value(floor))
+        .filter(collection_stats::Column::CollectionType.eq(collection_type))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Re-sums the rolling volume windows of `collection_type` and records `at` as the time of
+/// its latest change. Replayed events never move `updated_at` back.
+async fn refresh<C: ConnectionTrait>(
+    db: &C,
+    collection_type: &str,
+    at: NaiveDateTime,
+) -> Result<(), DbErr> {
+    update_windows(db, Some(collection_type), Utc::now().naive_utc()).await?;
+
+    collection_stats::Entity::update_many()
+        .col_expr(
+            collection_stats::Column::UpdatedAt,
+            Expr::cust_with_values("GREATEST(updated_at, $1)", [at]),
+        )
+        .filter(collection_stats::Column::CollectionType.eq(collection_type))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Re-sums the rolling volume windows of every collection as of `now`. Sales only refresh
+/// their own collection, so without this a collection that stops selling would keep
+/// showing volume that has left its windows.
+pub async fn refresh_volume_windows<C: ConnectionTrait>(
+    db: &C,
+    now: NaiveDateTime,
+) -> Result<(), DbErr> {
+    update_windows(db, None, now).await
+}
+
+/// Drops buckets older than a week and re-sums the windows ending with the hour of `now`,
+/// for one collection or for all of them.
+async fn update_windows<C: ConnectionTrait>(
+    db: &C,
+    collection_type: Option<&str>,
+    now: NaiveDateTime,
+) -> Result<(), DbErr> {
+    let (day_start, week_start) = window_starts(now);
+
+    let mut prune = collection_volume::Entity::delete_many()
+        .filter(collection_volume::Column::Hour.lt(week_start));
+    let mut update = collection_stats::Entity::update_many()
+        .col_expr(
+            collection_stats::Column::Volume24h,
+            window_volume(day_start),
+        )
+        .col_expr(
+            collection_stats::Column::Volume7d,
+            window_volume(week_start),
+        );
+    if let Some(collection_type) = collection_type {
+        prune = prune.filter(collection_volume::Column::CollectionType.eq(collection_type));
+        update = update.filter(collection_stats::Column::CollectionType.eq(collection_type));
+    }
+    prune.exec(db).await?;
+    update.exec(db).await?;
+
+    Ok(())
+}
+
+/// Volume of the updated row's collection since `since`.
+fn window_volume(since: NaiveDateTime) -> SimpleExpr {
+    Expr::cust_with_values(
+        "(SELECT CAST(COALESCE(SUM(volume), 0) AS BIGINT) FROM collection_volume \
+         WHERE collection_volume.collection_type = collection_stats.collection_type \
+         AND collection_volume.hour >= $1)",
+        [since],
+    )
+}
+
+fn hour_of(at: NaiveDateTime) -> NaiveDateTime {
+    at.with_minute(0)
+        .and_then(|at| at.with_second(0))
+        .and_then(|at| at.with_nanosecond(0))
+        .unwrap_or(at)
+}
+
+/// First buckets of the 24 hour and 7 day windows ending with the hour of `at`.
+fn window_starts(at: NaiveDateTime) -> (NaiveDateTime, NaiveDateTime) {
+    let hour = hour_of(at);
+    (
+        hour - Duration::hours(23),
+        hour - Duration::hours(24 * 7 - 1),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::DateTime;
+
+    use super::*;
+
+    #[test]
+    fn test_windows_cover_whole_hours() {
+        let at = DateTime::parse_from_rfc3339("2025-10-19T12:34:56.789Z")
+            .unwrap()
+            .naive_utc();
+        let (day_start, week_start) = window_starts(at);
+
+        assert_eq!(day_start.to_string(), "2025-10-18 13:00:00");
+        assert_eq!(week_start.to_string(), "2025-10-12 13:00:00");
+    }
+}
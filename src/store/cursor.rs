@@ -0,0 +1,47 @@
+use chrono::Utc;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{DatabaseConnection, DbErr, EntityTrait, Set};
+
+use crate::entity::indexer_state;
+
+/// Pipeline name used by the tip-following indexer loop.
+pub const LIVE_PIPELINE: &str = "live";
+
+/// Returns the last fully committed checkpoint for `pipeline`, if any.
+pub async fn load_cursor(db: &DatabaseConnection, pipeline: &str) -> Result<Option<u64>, DbErr> {
+    let state = indexer_state::Entity::find_by_id(pipeline.to_string())
+        .one(db)
+        .await?;
+
+    Ok(state.map(|state| state.checkpoint as u64))
+}
+
+/// Records `checkpoint` as the last fully committed checkpoint for `pipeline`.
+///
+/// Only call this once every write belonging to the checkpoint has been persisted,
+/// otherwise a restart would skip the unfinished work.
+pub async fn save_cursor(
+    db: &DatabaseConnection,
+    pipeline: &str,
+    checkpoint: u64,
+) -> Result<(), DbErr> {
+    let state = indexer_state::ActiveModel {
+        pipeline: Set(pipeline.to_string()),
+        checkpoint: Set(checkpoint as i64),
+        updated_at: Set(Utc::now().naive_utc()),
+    };
+
+    indexer_state::Entity::insert(state)
+        .on_conflict(
+            OnConflict::column(indexer_state::Column::Pipeline)
+                .update_columns([
+                    indexer_state::Column::Checkpoint,
+                    indexer_state::Column::UpdatedAt,
+                ])
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,208 @@
+use chrono::{DateTime, NaiveDateTime};
+use sea_orm::prelude::Expr;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::sea_query::extension::postgres::PgExpr;
+use sea_orm::{
+    ColumnTrait, Condition, ConnectionTrait, DbErr, EntityTrait, PaginatorTrait, QueryFilter, Set,
+};
+use serde_json::Value;
+
+use crate::entity::nft_event;
+use crate::store::store::ensure_wallet;
+
+/// Values of `NftEvent.type`, as shown in the activity feed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NftEventKind {
+    List,
+    UpdateListing,
+    Delist,
+    Buy,
+    Offer,
+    AcceptOffer,
+    DeclineOffer,
+    RevokeOffer,
+    ReceiptCreated,
+    ReceiptDestroyed,
+    AcceptCollectionOffer,
+    /// Collection offers have no NFT, so these rows are keyed by the offer ID.
+    RevokeCollectionOffer,
+    Mint,
+}
+
+impl NftEventKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NftEventKind::List => "list",
+            NftEventKind::UpdateListing => "update_listing",
+            NftEventKind::Delist => "delist",
+            NftEventKind::Buy => "buy",
+            NftEventKind::Offer => "offer",
+            NftEventKind::AcceptOffer => "accept_offer",
+            NftEventKind::DeclineOffer => "decline_offer",
+            NftEventKind::RevokeOffer => "revoke_offer",
+            NftEventKind::ReceiptCreated => "receipt_created",
+            NftEventKind::ReceiptDestroyed => "receipt_destroyed",
+            NftEventKind::AcceptCollectionOffer => "accept_collection_offer",
+            NftEventKind::RevokeCollectionOffer => "revoke_collection_offer",
+            NftEventKind::Mint => "mint",
+        }
+    }
+}
+
+/// Events that change whether and at which price an NFT is listed. A sale through an
+/// offer moves the NFT out of its kiosk, which ends any listing as well.
+pub const LISTING_EVENTS: &[NftEventKind] = &[
+    NftEventKind::List,
+    NftEventKind::UpdateListing,
+    NftEventKind::Delist,
+    NftEventKind::Buy,
+    NftEventKind::AcceptOffer,
+    NftEventKind::AcceptCollectionOffer,
+];
+
+/// Events that hand an NFT to a new owner.
+pub const SALE_EVENTS: &[NftEventKind] = &[
+    NftEventKind::Buy,
+    NftEventKind::AcceptOffer,
+    NftEventKind::AcceptCollectionOffer,
+];
+
+/// Events that close a single-NFT offer.
+pub const OFFER_CLOSING_EVENTS: &[NftEventKind] = &[
+    NftEventKind::AcceptOffer,
+    NftEventKind::DeclineOffer,
+    NftEventKind::RevokeOffer,
+];
+
+pub struct NewNftEvent {
+    pub kind: NftEventKind,
+    pub token_id: String,
+    pub collection_type: String,
+    pub digest: String,
+    pub price: Option<u64>,
+    pub marketplace_fee: Option<u64>,
+    pub royalty_fee: Option<u64>,
+    pub sender: String,
+    pub receiver: Option<String>,
+    /// Raw event payload kept for the activity feed.
+    pub description: Option<Value>,
+    pub timestamp_ms: u64,
+}
+
+/// Checkpoint timestamps are the clock for every event-derived row.
+pub fn event_time(timestamp_ms: u64) -> NaiveDateTime {
+    DateTime::from_timestamp_millis(timestamp_ms as i64)
+        .unwrap_or_default()
+        .naive_utc()
+}
+
+/// Appends an activity row. Returns `false` when the same event of the same transaction
+/// was already recorded, i.e. the checkpoint is being indexed again.
+pub async fn insert_nft_event<C: ConnectionTrait>(
+    db: &C,
+    event: NewNftEvent,
+) -> Result<bool, DbErr> {
+    ensure_wallet(db, &event.sender).await?;
+    if let Some(receiver) = &event.receiver {
+        ensure_wallet(db, receiver).await?;
+    }
+
+    let event_entity = nft_event::ActiveModel {
+        collection_type: Set(event.collection_type),
+        token_id: Set(event.token_id),
+        digest: Set(event.digest),
+        price: Set(event.price.map(|price| price as i64)),
+        marketplace_fee: Set(event.marketplace_fee.map(|fee| fee as i64)),
+        royalty_fee: Set(event.royalty_fee.map(|fee| fee as i64)),
+        description: Set(event.description),
+        sender: Set(event.sender),
+        reciever: Set(event.receiver),
+        r#type: Set(event.kind.as_str().to_string()),
+        created_at: Set(event_time(event.timestamp_ms)),
+    };
+
+    let inserted = nft_event::Entity::insert(event_entity)
+        .on_conflict(
+            OnConflict::columns([
+                nft_event::Column::TokenId,
+                nft_event::Column::Digest,
+                nft_event::Column::Type,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+
+    Ok(inserted > 0)
+}
+
+/// Whether one of `kinds` was recorded for `token_id` after `timestamp_ms`. A backfill or a
+/// re-indexed checkpoint can replay old events, and state derived from them must not
+/// overwrite state derived from newer ones.
+pub async fn has_newer_event<C: ConnectionTrait>(
+    db: &C,
+    token_id: &str,
+    kinds: &[NftEventKind],
+    timestamp_ms: u64,
+) -> Result<bool, DbErr> {
+    let newer = nft_event::Entity::find()
+        .filter(nft_event::Column::TokenId.eq(token_id))
+        .filter(nft_event::Column::Type.is_in(kinds.iter().map(|kind| kind.as_str())))
+        .filter(nft_event::Column::CreatedAt.gt(event_time(timestamp_ms)))
+        .count(db)
+        .await?;
+
+    Ok(newer > 0)
+}
+
+/// Whether the offer `offer_id` on `token_id` was already closed by one of `kinds`. Offer
+/// IDs are unique, so unlike [`has_newer_event`] no clock comparison is needed.
+pub async fn is_offer_closed<C: ConnectionTrait>(
+    db: &C,
+    token_id: &str,
+    offer_id: &str,
+    kinds: &[NftEventKind],
+) -> Result<bool, DbErr> {
+    let closed = nft_event::Entity::find()
+        .filter(nft_event::Column::TokenId.eq(token_id))
+        .filter(nft_event::Column::Type.is_in(kinds.iter().map(|kind| kind.as_str())))
+        .filter(
+            Expr::col(nft_event::Column::Description)
+                .cast_json_field("offer_id")
+                .eq(offer_id),
+        )
+        .count(db)
+        .await?;
+
+    Ok(closed > 0)
+}
+
+/// Whether the collection offer `offer_id` was already revoked or filled completely.
+pub async fn is_collection_offer_closed<C: ConnectionTrait>(
+    db: &C,
+    offer_id: &str,
+) -> Result<bool, DbErr> {
+    let last_fill = Condition::all()
+        .add(nft_event::Column::Type.eq(NftEventKind::AcceptCollectionOffer.as_str()))
+        .add(
+            Expr::col(nft_event::Column::Description)
+                .cast_json_field("offer_id")
+                .eq(offer_id),
+        )
+        .add(
+            Expr::col(nft_event::Column::Description)
+                .cast_json_field("remaining")
+                .eq("0"),
+        );
+    let revoked = Condition::all()
+        .add(nft_event::Column::Type.eq(NftEventKind::RevokeCollectionOffer.as_str()))
+        .add(nft_event::Column::TokenId.eq(offer_id));
+
+    let closed = nft_event::Entity::find()
+        .filter(Condition::any().add(last_fill).add(revoked))
+        .count(db)
+        .await?;
+
+    Ok(closed > 0)
+}
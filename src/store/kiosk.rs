@@ -0,0 +1,34 @@
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter};
+
+use crate::entity::kiosk;
+use crate::store::store::ensure_wallet;
+
+pub async fn insert_kiosk<C: ConnectionTrait>(db: &C, kiosk: kiosk::Model) -> Result<(), DbErr> {
+    ensure_wallet(db, &kiosk.owner_wallet_address).await?;
+
+    let kiosk_entity: kiosk::ActiveModel = kiosk.into();
+    kiosk::Entity::insert(kiosk_entity)
+        .on_conflict(
+            OnConflict::columns([kiosk::Column::KioskOwnercap, kiosk::Column::Kiosk])
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Wallet owning `kiosk`, if the kiosk was created through Hokko.
+pub async fn find_kiosk_owner<C: ConnectionTrait>(
+    db: &C,
+    kiosk: &str,
+) -> Result<Option<String>, DbErr> {
+    let kiosk = kiosk::Entity::find()
+        .filter(kiosk::Column::Kiosk.eq(kiosk))
+        .one(db)
+        .await?;
+
+    Ok(kiosk.map(|kiosk| kiosk.owner_wallet_address))
+}
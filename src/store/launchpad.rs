@@ -0,0 +1,228 @@
+use chrono::NaiveDateTime;
+use sea_orm::prelude::Expr;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, NotSet, QueryFilter, Set};
+
+use crate::entity::{launch_event, launchpad_collection, white_list_address};
+use crate::store::store::ensure_wallet;
+
+pub async fn insert_launch<C: ConnectionTrait>(
+    db: &C,
+    launch: launchpad_collection::Model,
+) -> Result<(), DbErr> {
+    ensure_wallet(db, &launch.owner_wallet_address).await?;
+
+    let launch_entity: launchpad_collection::ActiveModel = launch.into();
+    launchpad_collection::Entity::insert(launch_entity)
+        .on_conflict(
+            OnConflict::column(launchpad_collection::Column::CollectionType)
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn find_launch<C: ConnectionTrait>(
+    db: &C,
+    launch_id: &str,
+) -> Result<Option<launchpad_collection::Model>, DbErr> {
+    launchpad_collection::Entity::find()
+        .filter(launchpad_collection::Column::LaunchId.eq(launch_id))
+        .one(db)
+        .await
+}
+
+/// Records that event `event_seq` of `digest` was applied to `launch_id`. Returns `false`
+/// when it already was, i.e. the checkpoint is being indexed again.
+pub async fn mark_launch_event<C: ConnectionTrait>(
+    db: &C,
+    launch_id: &str,
+    digest: &str,
+    event_seq: u64,
+    kind: &str,
+    applied_at: NaiveDateTime,
+) -> Result<bool, DbErr> {
+    let event_entity = launch_event::ActiveModel {
+        digest: Set(digest.to_string()),
+        event_seq: Set(event_seq as i64),
+        launch_id: Set(launch_id.to_string()),
+        kind: Set(kind.to_string()),
+        created_at: Set(applied_at),
+    };
+
+    let inserted = launch_event::Entity::insert(event_entity)
+        .on_conflict(
+            OnConflict::columns([launch_event::Column::Digest, launch_event::Column::EventSeq])
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+
+    Ok(inserted > 0)
+}
+
+pub async fn set_launch_state<C: ConnectionTrait>(
+    db: &C,
+    launch_id: &str,
+    state: &str,
+    updated_at: NaiveDateTime,
+) -> Result<(), DbErr> {
+    let launch_entity = launchpad_collection::ActiveModel {
+        state: Set(state.to_string()),
+        updated_at: Set(updated_at),
+        ..Default::default()
+    };
+
+    launchpad_collection::Entity::update_many()
+        .set(launch_entity)
+        .filter(launchpad_collection::Column::LaunchId.eq(launch_id))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Bumps `updated_at`, which replayed events of the launch are compared against.
+pub async fn touch_launch<C: ConnectionTrait>(
+    db: &C,
+    launch_id: &str,
+    updated_at: NaiveDateTime,
+) -> Result<(), DbErr> {
+    let launch_entity = launchpad_collection::ActiveModel {
+        updated_at: Set(updated_at),
+        ..Default::default()
+    };
+
+    launchpad_collection::Entity::update_many()
+        .set(launch_entity)
+        .filter(launchpad_collection::Column::LaunchId.eq(launch_id))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn increment_mint_count<C: ConnectionTrait>(
+    db: &C,
+    launch_id: &str,
+    updated_at: NaiveDateTime,
+) -> Result<(), DbErr> {
+    launchpad_collection::Entity::update_many()
+        .col_expr(
+            launchpad_collection::Column::MintCount,
+            Expr::col(launchpad_collection::Column::MintCount).add(1),
+        )
+        .col_expr(
+            launchpad_collection::Column::UpdatedAt,
+            Expr::value(updated_at),
+        )
+        .filter(launchpad_collection::Column::LaunchId.eq(launch_id))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+pub struct LaunchTimes {
+    pub start_time: NaiveDateTime,
+    pub white_list_start_time: NaiveDateTime,
+    pub custom_start_time: NaiveDateTime,
+}
+
+pub async fn set_launch_times<C: ConnectionTrait>(
+    db: &C,
+    launch_id: &str,
+    times: LaunchTimes,
+    updated_at: NaiveDateTime,
+) -> Result<(), DbErr> {
+    let launch_entity = launchpad_collection::ActiveModel {
+        start_time: Set(times.start_time),
+        white_list_start_time: Set(times.white_list_start_time),
+        custom_start_time: Set(times.custom_start_time),
+        updated_at: Set(updated_at),
+        ..Default::default()
+    };
+
+    launchpad_collection::Entity::update_many()
+        .set(launch_entity)
+        .filter(launchpad_collection::Column::LaunchId.eq(launch_id))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Sets the whitelist allocation of `address` for `launch_id`. Allocations the address has
+/// for other launches are kept.
+pub async fn upsert_whitelist_address<C: ConnectionTrait>(
+    db: &C,
+    launch_id: &str,
+    address: &str,
+    allocation: i32,
+) -> Result<(), DbErr> {
+    ensure_wallet(db, address).await?;
+
+    let address_entity = white_list_address::ActiveModel {
+        address: Set(address.to_string()),
+        allocation: Set(allocation),
+        launch_id: Set(launch_id.to_string()),
+        name: NotSet,
+    };
+
+    white_list_address::Entity::insert(address_entity)
+        .on_conflict(
+            OnConflict::columns([
+                white_list_address::Column::LaunchId,
+                white_list_address::Column::Address,
+            ])
+            .update_column(white_list_address::Column::Allocation)
+            .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn remove_whitelist_addresses<C: ConnectionTrait>(
+    db: &C,
+    launch_id: &str,
+    addresses: &[String],
+) -> Result<(), DbErr> {
+    if addresses.is_empty() {
+        return Ok(());
+    }
+
+    white_list_address::Entity::delete_many()
+        .filter(white_list_address::Column::LaunchId.eq(launch_id))
+        .filter(white_list_address::Column::Address.is_in(addresses.iter().cloned()))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Uses up one whitelist mint of `address`. Returns `false` when the address had no
+/// allocation left for this launch.
+pub async fn decrement_whitelist_allocation<C: ConnectionTrait>(
+    db: &C,
+    launch_id: &str,
+    address: &str,
+) -> Result<bool, DbErr> {
+    let result = white_list_address::Entity::update_many()
+        .col_expr(
+            white_list_address::Column::Allocation,
+            Expr::col(white_list_address::Column::Allocation).sub(1),
+        )
+        .filter(white_list_address::Column::LaunchId.eq(launch_id))
+        .filter(white_list_address::Column::Address.eq(address))
+        .filter(white_list_address::Column::Allocation.gt(0))
+        .exec(db)
+        .await?;
+
+    Ok(result.rows_affected > 0)
+}
@@ -0,0 +1,135 @@
+use chrono::NaiveDateTime;
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter, Set, TransactionTrait,
+};
+
+use crate::entity::listing;
+use crate::store::collection_stats::{listing_added, listing_removed, listing_repriced};
+use crate::store::store::ensure_wallet;
+
+/// Makes `listing` the only active listing of its NFT. A relisted NFT gets a new purchase
+/// cap, so any row left over from the previous listing is dropped.
+///
+/// Listing writes and the collection stats they move commit together, in a savepoint when
+/// `db` is already a transaction.
+pub async fn replace_listing<C: ConnectionTrait + TransactionTrait>(
+    db: &C,
+    listing: listing::Model,
+) -> Result<(), DbErr> {
+    let txn = db.begin().await?;
+    ensure_wallet(&txn, &listing.owner_wallet_address).await?;
+    remove_token_listings(&txn, &listing.token_id, listing.updated_at).await?;
+
+    let (collection_type, price, listed_at) = (
+        listing.collection_type.clone(),
+        listing.price,
+        listing.updated_at,
+    );
+    let listing_entity: listing::ActiveModel = listing.into();
+    listing::Entity::insert(listing_entity)
+        .exec_without_returning(&txn)
+        .await?;
+    listing_added(&txn, &collection_type, price, listed_at).await?;
+    txn.commit().await?;
+
+    Ok(())
+}
+
+pub async fn find_listing<C: ConnectionTrait>(
+    db: &C,
+    shared_purchase_cap: &str,
+    token_id: &str,
+) -> Result<Option<listing::Model>, DbErr> {
+    listing::Entity::find_by_id((shared_purchase_cap.to_string(), token_id.to_string()))
+        .one(db)
+        .await
+}
+
+pub struct ListingPrice {
+    pub price: u64,
+    pub marketplace_fee: u64,
+    pub royalty_fee: u64,
+}
+
+/// Returns the number of updated rows; zero when the listing is unknown.
+pub async fn update_listing_price<C: ConnectionTrait + TransactionTrait>(
+    db: &C,
+    shared_purchase_cap: &str,
+    token_id: &str,
+    price: ListingPrice,
+    updated_at: NaiveDateTime,
+) -> Result<u64, DbErr> {
+    let txn = db.begin().await?;
+    let Some(previous) =
+        listing::Entity::find_by_id((shared_purchase_cap.to_string(), token_id.to_string()))
+            .one(&txn)
+            .await?
+    else {
+        return Ok(0);
+    };
+
+    let listing_entity = listing::ActiveModel {
+        price: Set(price.price as i64),
+        marketplace_fee: Set(price.marketplace_fee as i64),
+        royalty_fee: Set(price.royalty_fee as i64),
+        updated_at: Set(updated_at),
+        ..Default::default()
+    };
+
+    let result = listing::Entity::update_many()
+        .set(listing_entity)
+        .filter(listing::Column::SharedPurchaseCap.eq(shared_purchase_cap))
+        .filter(listing::Column::TokenId.eq(token_id))
+        .exec(&txn)
+        .await?;
+    listing_repriced(
+        &txn,
+        &previous.collection_type,
+        previous.price,
+        price.price as i64,
+        updated_at,
+    )
+    .await?;
+    txn.commit().await?;
+
+    Ok(result.rows_affected)
+}
+
+pub async fn remove_listing<C: ConnectionTrait + TransactionTrait>(
+    db: &C,
+    shared_purchase_cap: &str,
+    token_id: &str,
+    removed_at: NaiveDateTime,
+) -> Result<(), DbErr> {
+    let txn = db.begin().await?;
+    let removed = listing::Entity::delete_many()
+        .filter(listing::Column::SharedPurchaseCap.eq(shared_purchase_cap))
+        .filter(listing::Column::TokenId.eq(token_id))
+        .exec_with_returning(&txn)
+        .await?;
+    for listing in removed {
+        listing_removed(&txn, &listing.collection_type, listing.price, removed_at).await?;
+    }
+    txn.commit().await?;
+
+    Ok(())
+}
+
+/// Drops every listing of `token_id`, used when the NFT changes hands outside the listing.
+pub async fn remove_token_listings<C: ConnectionTrait + TransactionTrait>(
+    db: &C,
+    token_id: &str,
+    removed_at: NaiveDateTime,
+) -> Result<(), DbErr> {
+    let txn = db.begin().await?;
+    let removed = listing::Entity::delete_many()
+        .filter(listing::Column::TokenId.eq(token_id))
+        .exec_with_returning(&txn)
+        .await?;
+    for listing in removed {
+        listing_removed(&txn, &listing.collection_type, listing.price, removed_at).await?;
+    }
+    txn.commit().await?;
+
+    Ok(())
+}
@@ -1 +1,9 @@
+pub mod collection_offer;
+pub mod collection_stats;
+pub mod cursor;
+pub mod event;
+pub mod kiosk;
+pub mod launchpad;
+pub mod listing;
+pub mod offer;
 pub mod store;
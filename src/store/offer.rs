@@ -0,0 +1,35 @@
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter};
+
+use crate::entity::offer;
+use crate::store::store::ensure_wallet;
+
+pub async fn insert_offer<C: ConnectionTrait>(db: &C, offer: offer::Model) -> Result<(), DbErr> {
+    ensure_wallet(db, &offer.owner_wallet_address).await?;
+
+    let offer_entity: offer::ActiveModel = offer.into();
+    offer::Entity::insert(offer_entity)
+        .on_conflict(
+            OnConflict::columns([offer::Column::OfferId, offer::Column::TokenId])
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn remove_offer<C: ConnectionTrait>(
+    db: &C,
+    offer_id: &str,
+    token_id: &str,
+) -> Result<(), DbErr> {
+    offer::Entity::delete_many()
+        .filter(offer::Column::OfferId.eq(offer_id))
+        .filter(offer::Column::TokenId.eq(token_id))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
@@ -1,51 +1,78 @@
 use crate::{
     AppState,
-    entity::{collection, nft},
+    entity::{collection, nft, wallet},
     processor::model::{NftData, OwnerType},
 };
-use chrono::Utc;
+use std::str::FromStr;
+
+use chrono::{NaiveDateTime, Utc};
 use sea_orm::prelude::Expr;
-use sea_orm::sea_query::OnConflict;
 use sea_orm::sea_query::extension::postgres::PgExpr;
-use sea_orm::{EntityTrait, sea_query::Func};
+use sea_orm::sea_query::{Alias, Func, OnConflict, SimpleExpr};
+use sea_orm::{ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter};
 use sea_orm::{NotSet, Set};
+use sui_sdk::types::base_types::SuiAddress;
 
-pub async fn store_nft(app_state: AppState, nft: NftData) {
+pub async fn store_nft(app_state: AppState, nft: NftData) -> Result<(), DbErr> {
     let db = app_state.db;
 
+    let placeholder_name = placeholder_name(&nft.collection_type);
     let collection_entity = collection::ActiveModel {
         r#type: Set(nft.collection_type.clone()),
         verified: Set(false),
         banner_url: Set(nft.banner_url),
         image_url: Set(nft.cover_url.unwrap_or_else(|| "".to_string())),
-        name: Set(nft.collection_name.unwrap_or_else(|| {
-            nft.collection_type
-                .clone()
-                .split("::")
-                .last()
-                .unwrap_or(&nft.collection_type)
-                .to_string()
-        })),
+        name: Set(nft
+            .collection_name
+            .unwrap_or_else(|| placeholder_name.clone())),
         volume: Set(0),
         description: Set(nft.collection_description.unwrap_or_else(|| "".to_string())),
         created_at: Set(Utc::now().naive_utc()),
         updated_at: Set(Utc::now().naive_utc()),
     };
 
-    let _res = collection::Entity::insert(collection_entity)
+    // Collections created by `ensure_collection` only hold placeholders until an NFT is seen
+    collection::Entity::insert(collection_entity)
         .on_conflict(
             OnConflict::column(collection::Column::Type)
-                .do_nothing()
+                .value(
+                    collection::Column::Name,
+                    fill_placeholder(collection::Column::Name, &placeholder_name),
+                )
+                .value(
+                    collection::Column::ImageUrl,
+                    fill_placeholder(collection::Column::ImageUrl, ""),
+                )
+                .value(
+                    collection::Column::Description,
+                    fill_placeholder(collection::Column::Description, ""),
+                )
+                .value(
+                    collection::Column::BannerUrl,
+                    Func::coalesce([
+                        Expr::col((collection::Entity, collection::Column::BannerUrl)),
+                        Expr::col((Alias::new("excluded"), collection::Column::BannerUrl)),
+                    ]),
+                )
                 .to_owned(),
         )
-        .exec(db.as_ref())
-        .await;
+        .exec_without_returning(db.as_ref())
+        .await?;
 
     let item_holder = nft.item_holder.unwrap_or_else(|| OwnerType {
         address_owner: Some("0x000".to_string()),
         object_owner: Some("0x000".to_string()),
     });
 
+    // Shared, immutable and unresolved owners are stored as placeholders, not wallets
+    let wallet_address = item_holder
+        .address_owner
+        .clone()
+        .filter(|address| is_sui_address(address));
+    if let Some(address) = &wallet_address {
+        ensure_wallet(db.as_ref(), address).await?;
+    }
+
     let nft_entity = nft::ActiveModel {
         token_id: Set(nft.token_id.clone()),
         r#type: Set(nft.collection_type),
@@ -62,9 +89,7 @@ pub async fn store_nft(app_state: AppState, nft: NftData) {
             nft.digest.clone().unwrap_or_else(|| "".to_string()),
         ])),
         volume: Set(0),
-        wallet_address: Set(Some(
-            item_holder.address_owner.unwrap_or_else(|| "".to_string()),
-        )),
+        wallet_address: Set(wallet_address),
         rarity_score: nft
             .rarity_score
             .map_or_else(|| NotSet, |score| Set(Some(score as i32))),
@@ -75,7 +100,7 @@ pub async fn store_nft(app_state: AppState, nft: NftData) {
         updated_at: Set(Utc::now().naive_utc()),
     };
 
-    let _res = nft::Entity::insert(nft_entity)
+    nft::Entity::insert(nft_entity)
         .on_conflict(
             OnConflict::column(nft::Column::TokenId)
                 .update_columns([
@@ -95,6 +120,175 @@ pub async fn store_nft(app_state: AppState, nft: NftData) {
                 )
                 .to_owned(),
         )
-        .exec(db.as_ref())
-        .await;
+        .exec_without_returning(db.as_ref())
+        .await?;
+
+    Ok(())
+}
+
+/// Inserts a placeholder collection named after its type unless it is already known, so
+/// rows that reference a collection never trip its foreign key. `store_nft` replaces the
+/// placeholder name and the empty display fields once an NFT of the collection is indexed.
+pub async fn ensure_collection<C: ConnectionTrait>(
+    db: &C,
+    collection_type: &str,
+) -> Result<(), DbErr> {
+    let collection_entity = collection::ActiveModel {
+        r#type: Set(collection_type.to_string()),
+        verified: Set(false),
+        banner_url: Set(None),
+        image_url: Set("".to_string()),
+        name: Set(placeholder_name(collection_type)),
+        volume: Set(0),
+        description: Set("".to_string()),
+        created_at: Set(Utc::now().naive_utc()),
+        updated_at: Set(Utc::now().naive_utc()),
+    };
+
+    collection::Entity::insert(collection_entity)
+        .on_conflict(
+            OnConflict::column(collection::Column::Type)
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+
+    Ok(())
+}
+
+fn is_sui_address(value: &str) -> bool {
+    SuiAddress::from_str(value).is_ok()
+}
+
+/// Name given to a collection before its display data is known.
+fn placeholder_name(collection_type: &str) -> String {
+    collection_type
+        .split("::")
+        .last()
+        .unwrap_or(collection_type)
+        .to_string()
+}
+
+/// Takes the incoming value of `column` only while the stored one is still `placeholder`.
+fn fill_placeholder(column: collection::Column, placeholder: &str) -> SimpleExpr {
+    Expr::case(
+        Expr::col((collection::Entity, column)).eq(placeholder),
+        Expr::col((Alias::new("excluded"), column)),
+    )
+    .finally(Expr::col((collection::Entity, column)))
+    .into()
+}
+
+/// Inserts `address` into the wallet table unless it is already known, so rows that
+/// reference a wallet never trip its foreign key.
+pub async fn ensure_wallet<C: ConnectionTrait>(db: &C, address: &str) -> Result<(), DbErr> {
+    let wallet_entity = wallet::ActiveModel {
+        address: Set(address.to_string()),
+        personal_fee: NotSet,
+    };
+
+    wallet::Entity::insert(wallet_entity)
+        .on_conflict(
+            OnConflict::column(wallet::Column::Address)
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Sets the personal marketplace fee of `address`, creating the wallet if needed.
+pub async fn set_personal_fee<C: ConnectionTrait>(
+    db: &C,
+    address: &str,
+    personal_fee: i32,
+) -> Result<(), DbErr> {
+    let wallet_entity = wallet::ActiveModel {
+        address: Set(address.to_string()),
+        personal_fee: Set(Some(personal_fee)),
+    };
+
+    wallet::Entity::insert(wallet_entity)
+        .on_conflict(
+            OnConflict::column(wallet::Column::Address)
+                .update_column(wallet::Column::PersonalFee)
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Moves `token_id` to `buyer` after a sale and records the sale price. `kiosk` is the
+/// kiosk the NFT ends up in, when the sale event tells. Otherwise the kiosk is cleared,
+/// since the NFT has left the seller's kiosk; the column stores an unknown kiosk as empty.
+pub async fn record_sale<C: ConnectionTrait>(
+    db: &C,
+    token_id: &str,
+    buyer: &str,
+    kiosk: Option<&str>,
+    price: u64,
+    sold_at: NaiveDateTime,
+) -> Result<(), DbErr> {
+    ensure_wallet(db, buyer).await?;
+
+    nft::Entity::update_many()
+        .col_expr(nft::Column::Holder, Expr::value(buyer))
+        .col_expr(nft::Column::WalletAddress, Expr::value(buyer))
+        .col_expr(nft::Column::Kiosk, Expr::value(kiosk.unwrap_or_default()))
+        .col_expr(nft::Column::LastSale, Expr::value(price as i64))
+        .col_expr(nft::Column::UpdatedAt, Expr::value(sold_at))
+        .filter(nft::Column::TokenId.eq(token_id))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Adds a sale to the traded volume of the NFT and of its collection. Must run once per
+/// sale; callers tie it to the insertion of the sale's activity row.
+pub async fn add_sale_volume<C: ConnectionTrait>(
+    db: &C,
+    token_id: &str,
+    collection_type: &str,
+    price: u64,
+) -> Result<(), DbErr> {
+    nft::Entity::update_many()
+        .col_expr(
+            nft::Column::Volume,
+            Expr::col(nft::Column::Volume).add(price as i64),
+        )
+        .filter(nft::Column::TokenId.eq(token_id))
+        .exec(db)
+        .await?;
+
+    collection::Entity::update_many()
+        .col_expr(
+            collection::Column::Volume,
+            Expr::col(collection::Column::Volume).add(price as i64),
+        )
+        .filter(collection::Column::Type.eq(collection_type))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_sui_addresses_become_wallets() {
+        assert!(is_sui_address(
+            "0x57191e5e5c41166b90a4b7811ad3ec7963708aa537a8438c1761a5d33e2155fd"
+        ));
+        for placeholder in ["0x000", "Shared", "Immutable", "error", ""] {
+            assert!(!is_sui_address(placeholder), "{placeholder}");
+        }
+    }
 }